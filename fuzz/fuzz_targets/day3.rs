@@ -0,0 +1,11 @@
+#![no_main]
+
+use advent_of_code_2021::day3::Day3;
+use advent_of_code_2021::Solution;
+use libfuzzer_sys::fuzz_target;
+
+// The generator must reject invalid input with an error instead of panicking.
+fuzz_target!(|data: &[u8]| {
+    let input = String::from_utf8_lossy(data);
+    let _ = Day3::parse(&input);
+});
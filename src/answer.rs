@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The answer to a part of a puzzle.
+///
+/// Integers are stored as `Integer` when they fit in an `i64`, and as `BigInteger` otherwise. The
+/// two variants compare equal when they hold the same value.
+#[derive(Clone, Debug, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Answer {
+    Integer(i64),
+    BigInteger(i128),
+    Text(String),
+    /// A multi-line drawing, stored as a list of lines.
+    Art(Vec<String>),
+}
+
+impl Answer {
+    /// Compares the answer with an answer as it is recorded in a single line of text.
+    ///
+    /// The lines of an `Art` answer are recorded separated by a literal `\n`.
+    pub fn matches(&self, recorded: &str) -> bool {
+        match self {
+            Self::Art(lines) => recorded.split("\\n").eq(lines.iter().map(String::as_str)),
+            _ => self.to_string() == recorded,
+        }
+    }
+
+    /// Returns the answer formatted on a single line, in the format expected by `matches()`.
+    pub fn to_record(&self) -> String {
+        match self {
+            Self::Art(lines) => lines.join("\\n"),
+            _ => self.to_string(),
+        }
+    }
+
+    const fn as_integer(&self) -> Option<i128> {
+        match self {
+            Self::Integer(v) => Some(*v as i128),
+            Self::BigInteger(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Text(a), Self::Text(b)) => a == b,
+            (Self::Art(a), Self::Art(b)) => a == b,
+            _ => self.as_integer().is_some() && self.as_integer() == other.as_integer(),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(v) => write!(f, "{}", v),
+            Self::BigInteger(v) => write!(f, "{}", v),
+            Self::Text(text) => write!(f, "{}", text),
+            Self::Art(lines) => write!(f, "{}", lines.join("\n")),
+        }
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(v: i128) -> Self {
+        i64::try_from(v).map_or(Self::BigInteger(v), Self::Integer)
+    }
+}
+
+macro_rules! from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(v: $t) -> Self {
+                    i64::try_from(v).map_or_else(|_| Self::from(v as i128), Self::Integer)
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(i32, i64, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(lines: Vec<String>) -> Self {
+        Self::Art(lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from() {
+        assert_eq!(Answer::Integer(1521), Answer::from(1521_usize));
+        assert_eq!(Answer::Integer(-3), Answer::from(-3_i32));
+        assert!(matches!(
+            Answer::from(u64::MAX),
+            Answer::BigInteger(v) if v == u64::MAX as i128
+        ));
+        assert_eq!(Answer::BigInteger(5), Answer::Integer(5));
+        assert_ne!(Answer::Text(String::from("5")), Answer::Integer(5));
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(Answer::from(1609314870967_u64).matches("1609314870967"));
+        assert!(!Answer::from(1609314870967_u64).matches("1609314870968"));
+        assert!(Answer::from("EFLFJGRF").matches("EFLFJGRF"));
+
+        let art = Answer::from(vec![String::from("#.#"), String::from(".#.")]);
+        assert_eq!("#.#\n.#.", art.to_string());
+        assert_eq!("#.#\\n.#.", art.to_record());
+        assert!(art.matches("#.#\\n.#."));
+        assert!(!art.matches("#.#"));
+    }
+
+    #[test]
+    fn test_json() {
+        let answers = vec![
+            Answer::from(1521_usize),
+            Answer::BigInteger(i128::MAX),
+            Answer::from("EFLFJGRF"),
+            Answer::from(vec![String::from("#.#")]),
+        ];
+        let json = serde_json::to_string(&answers).unwrap();
+        assert!(json.starts_with(r#"[{"type":"integer","value":1521},"#));
+        assert_eq!(answers, serde_json::from_str::<Vec<Answer>>(&json).unwrap());
+    }
+}
@@ -0,0 +1,134 @@
+use crate::answer::Answer;
+use crate::runner::{Part, RunError};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The recorded answers of the puzzles.
+///
+/// Answers are stored in a text file with one answer per line, formatted as `<day> <part>
+/// <answer>`. Empty lines and lines starting with `#` are ignored. The lines of a multi-line answer
+/// are separated by a literal `\n`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(u8, Part), String>,
+}
+
+/// The result of comparing an answer with the recorded one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// The answer matches the recorded answer.
+    Pass,
+    /// The answer differs from the recorded answer.
+    Fail { expected: String },
+    /// There is no recorded answer.
+    Missing,
+}
+
+impl Answers {
+    /// Parses the contents of an answers file.
+    ///
+    /// On failure the 1-based number of the offending line is returned.
+    pub fn parse(input: &str) -> Result<Self, usize> {
+        let mut answers = BTreeMap::new();
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.splitn(3, ' ');
+            let day = fields.next().and_then(|d| d.parse().ok());
+            let part = fields
+                .next()
+                .and_then(|p| p.parse().ok())
+                .and_then(Part::from_number);
+            let answer = fields.next().map(str::trim).filter(|a| !a.is_empty());
+            match (day, part, answer) {
+                (Some(day), Some(part), Some(answer)) => {
+                    answers.insert((day, part), answer.to_string());
+                }
+                _ => return Err(i + 1),
+            }
+        }
+        Ok(Self { answers })
+    }
+
+    /// Loads the answers file at the given path. A missing file has no answers.
+    pub fn load(path: &Path) -> Result<Self, RunError> {
+        let input = match fs::read_to_string(path) {
+            Ok(input) => input,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => {
+                return Err(RunError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            }
+        };
+        Self::parse(&input).map_err(|line| RunError::Answers {
+            path: path.to_path_buf(),
+            line,
+        })
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    /// Compares an answer with the recorded answer.
+    pub fn check(&self, day: u8, part: Part, answer: &Answer) -> Verdict {
+        match self.get(day, part) {
+            None => Verdict::Missing,
+            Some(expected) if answer.matches(expected) => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse(get_test_input()).unwrap();
+        assert_eq!(Some("1521"), answers.get(1, Part::One));
+        assert_eq!(Some("1543"), answers.get(1, Part::Two));
+        assert_eq!(None, answers.get(2, Part::One));
+
+        assert_eq!(Err(2), Answers::parse("1 1 1521\n1 3 1543\n"));
+        assert_eq!(Err(1), Answers::parse("1 1\n"));
+    }
+
+    #[test]
+    fn test_check() {
+        let answers = Answers::parse(get_test_input()).unwrap();
+        assert_eq!(
+            Verdict::Pass,
+            answers.check(1, Part::One, &Answer::Integer(1521))
+        );
+        assert_eq!(
+            Verdict::Fail {
+                expected: String::from("1543")
+            },
+            answers.check(1, Part::Two, &Answer::Integer(1542))
+        );
+        assert_eq!(
+            Verdict::Missing,
+            answers.check(2, Part::One, &Answer::Integer(150))
+        );
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+            # day part answer
+            1 1 1521
+
+            1 2 1543
+        "}
+    }
+}
@@ -0,0 +1,195 @@
+use crate::runner::{Part, Puzzle, RunError};
+use serde::Serialize;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// A phase of solving a puzzle that is timed separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    Generator,
+    Part1,
+    Part2,
+}
+
+impl From<Part> for Phase {
+    fn from(part: Part) -> Self {
+        match part {
+            Part::One => Self::Part1,
+            Part::Two => Self::Part2,
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Generator => write!(f, "generator"),
+            Self::Part1 => write!(f, "part1"),
+            Self::Part2 => write!(f, "part2"),
+        }
+    }
+}
+
+/// Timing statistics of a phase over a number of runs. All durations are in nanoseconds.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Measurement {
+    pub day: u8,
+    pub phase: Phase,
+    pub runs: usize,
+    pub min: u64,
+    pub median: u64,
+    pub mean: u64,
+    pub max: u64,
+    pub std_dev: u64,
+}
+
+impl Measurement {
+    /// Calculates the statistics for the given samples.
+    pub fn new(day: u8, phase: Phase, samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "at least one sample is needed");
+        let mut samples: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64).collect();
+        samples.sort_unstable_by(f64::total_cmp);
+
+        let runs = samples.len();
+        let mean = samples.iter().sum::<f64>() / runs as f64;
+        let median = if runs.is_multiple_of(2) {
+            (samples[runs / 2 - 1] + samples[runs / 2]) / 2.0
+        } else {
+            samples[runs / 2]
+        };
+        let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / runs as f64;
+
+        Self {
+            day,
+            phase,
+            runs,
+            min: samples[0] as u64,
+            median: median as u64,
+            mean: mean as u64,
+            max: samples[runs - 1] as u64,
+            std_dev: variance.sqrt() as u64,
+        }
+    }
+}
+
+/// The measurements of a benchmark run.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Report {
+    pub measurements: Vec<Measurement>,
+}
+
+impl Report {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a report can always be serialized")
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("day,phase,runs,min,median,mean,max,std_dev\n");
+        for m in &self.measurements {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                m.day, m.phase, m.runs, m.min, m.median, m.mean, m.max, m.std_dev
+            ));
+        }
+        csv
+    }
+}
+
+impl fmt::Display for Report {
+    /// Formats the report as a table with human readable durations.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let duration = |ns: u64| format!("{:.1?}", Duration::from_nanos(ns));
+        writeln!(
+            f,
+            "{:>3}  {:<9}  {:>5}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}",
+            "Day", "Phase", "Runs", "Min", "Median", "Mean", "Max", "Std dev"
+        )?;
+        for m in &self.measurements {
+            writeln!(
+                f,
+                "{:>3}  {:<9}  {:>5}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}",
+                m.day,
+                m.phase.to_string(),
+                m.runs,
+                duration(m.min),
+                duration(m.median),
+                duration(m.mean),
+                duration(m.max),
+                duration(m.std_dev)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Times the generator and both parts of a puzzle.
+///
+/// Every phase is run once to warm up, followed by the given number of timed runs.
+pub fn bench(puzzle: &Puzzle, input: &str, runs: usize) -> Result<Vec<Measurement>, RunError> {
+    let runs = runs.max(1);
+
+    let parsed = puzzle.parse(input)?;
+    let mut samples = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let start = Instant::now();
+        // Keep the result until the end of the iteration so dropping it is not timed.
+        let _parsed = puzzle.parse(input)?;
+        samples.push(start.elapsed());
+    }
+    let mut measurements = vec![Measurement::new(puzzle.day, Phase::Generator, &samples)];
+
+    for part in Part::ALL {
+        puzzle.solve(part, &parsed)?;
+        samples.clear();
+        for _ in 0..runs {
+            let start = Instant::now();
+            puzzle.solve(part, &parsed)?;
+            samples.push(start.elapsed());
+        }
+        measurements.push(Measurement::new(puzzle.day, part.into(), &samples));
+    }
+
+    Ok(measurements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "day6")]
+    use crate::runner;
+
+    #[test]
+    fn test_measurement() {
+        let samples: Vec<Duration> = [4, 1, 3, 2]
+            .iter()
+            .map(|s| Duration::from_nanos(*s))
+            .collect();
+        let measurement = Measurement::new(1, Phase::Part1, &samples);
+        assert_eq!(1, measurement.min);
+        assert_eq!(2, measurement.median);
+        assert_eq!(2, measurement.mean);
+        assert_eq!(4, measurement.max);
+        assert_eq!(1, measurement.std_dev);
+    }
+
+    #[test]
+    #[cfg(feature = "day6")]
+    fn test_bench() {
+        let puzzle = runner::puzzle(6).unwrap();
+        let report = Report {
+            measurements: bench(puzzle, "3,4,3,1,2\n", 3).unwrap(),
+        };
+        let phases: Vec<Phase> = report.measurements.iter().map(|m| m.phase).collect();
+        assert_eq!(vec![Phase::Generator, Phase::Part1, Phase::Part2], phases);
+        assert!(report.measurements.iter().all(|m| m.runs == 3));
+
+        let csv = report.to_csv();
+        assert_eq!(4, csv.lines().count());
+        assert!(csv.lines().nth(1).unwrap().starts_with("6,generator,3,"));
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!("part2", json[2]["phase"]);
+    }
+}
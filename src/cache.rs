@@ -0,0 +1,161 @@
+//! Caching of answers on disk, so inputs that did not change are not solved again.
+
+use crate::answer::Answer;
+use crate::runner::{Part, Puzzle, RunError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The default location of the answer cache. It is kept in the build directory, so `cargo clean`
+/// removes it.
+pub const CACHE_PATH: &str = "target/answers-cache.json";
+
+/// Returns the 64-bit FNV-1a hash of the given bytes.
+///
+/// This is not a cryptographic hash, but it is fast, stable across platforms and Rust versions, and
+/// can be calculated at compile time.
+pub const fn fingerprint(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        i += 1;
+    }
+    hash
+}
+
+/// Answers that were calculated before, stored on disk.
+///
+/// An answer is stored with the fingerprint of the input and the version of the solver of the day.
+/// It is only used for the same input, as long as the source of the solver does not change.
+#[derive(Debug)]
+pub struct Cache {
+    path: PathBuf,
+    entries: BTreeMap<(u8, u8, u64), (u64, Answer)>,
+    changed: bool,
+}
+
+/// An answer as it is stored in the cache file.
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    day: u8,
+    part: u8,
+    input: u64,
+    version: u64,
+    answer: Answer,
+}
+
+impl Cache {
+    /// Loads the cache from the given file. The cache starts empty if the file is missing or
+    /// cannot be read, since every answer in it can be calculated again.
+    pub fn load(path: &Path) -> Self {
+        let entries: Vec<Entry> = fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        Self {
+            path: path.to_path_buf(),
+            entries: entries
+                .into_iter()
+                .map(|e| ((e.day, e.part, e.input), (e.version, e.answer)))
+                .collect(),
+            changed: false,
+        }
+    }
+
+    /// Returns the cached answer for an input with the given fingerprint.
+    ///
+    /// Nothing is returned if the answer was calculated by a different version of the solver, or
+    /// if the puzzle has no version.
+    pub fn get(&self, puzzle: &Puzzle, part: Part, input: u64) -> Option<&Answer> {
+        let version = puzzle.version()?;
+        self.entries
+            .get(&(puzzle.day, part.number(), input))
+            .filter(|(v, _)| *v == version)
+            .map(|(_, answer)| answer)
+    }
+
+    /// Stores an answer for an input with the given fingerprint, replacing the answer of an
+    /// earlier version of the solver.
+    pub fn insert(&mut self, puzzle: &Puzzle, part: Part, input: u64, answer: Answer) {
+        if let Some(version) = puzzle.version() {
+            let key = (puzzle.day, part.number(), input);
+            let entry = (version, answer);
+            if self.entries.get(&key) != Some(&entry) {
+                self.entries.insert(key, entry);
+                self.changed = true;
+            }
+        }
+    }
+
+    /// Writes the cache to its file, if answers were added.
+    pub fn save(&mut self) -> Result<(), RunError> {
+        if !self.changed {
+            return Ok(());
+        }
+        let entries: Vec<Entry> = self
+            .entries
+            .iter()
+            .map(|(&(day, part, input), (version, answer))| Entry {
+                day,
+                part,
+                input,
+                version: *version,
+                answer: answer.clone(),
+            })
+            .collect();
+        let json = serde_json::to_string_pretty(&entries).expect("answers can be serialised");
+        let write_error = |source| RunError::Write {
+            path: self.path.clone(),
+            source,
+        };
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(write_error)?;
+        }
+        fs::write(&self.path, json + "\n").map_err(write_error)?;
+        self.changed = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(all(feature = "day1", feature = "day2"))]
+    use crate::runner;
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(0xcbf2_9ce4_8422_2325, fingerprint(b""));
+        assert_eq!(0xaf63_dc4c_8601_ec8c, fingerprint(b"a"));
+        assert_ne!(fingerprint(b"199\n200\n"), fingerprint(b"199\n201\n"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "day1", feature = "day2"))]
+    fn test_cache() {
+        let path = std::env::temp_dir().join("advent_of_code_2021_test_cache.json");
+        let puzzle = runner::puzzle(1).unwrap();
+        let input = fingerprint(b"199\n200\n");
+
+        let mut cache = Cache::load(&path);
+        assert_eq!(None, cache.get(puzzle, Part::One, input));
+        cache.insert(puzzle, Part::One, input, Answer::Integer(1));
+        cache.save().unwrap();
+
+        let cache = Cache::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            Some(&Answer::Integer(1)),
+            cache.get(puzzle, Part::One, input)
+        );
+        assert_eq!(None, cache.get(puzzle, Part::Two, input));
+        assert_eq!(None, cache.get(puzzle, Part::One, input + 1));
+        assert_eq!(
+            None,
+            cache.get(runner::puzzle(2).unwrap(), Part::One, input)
+        );
+    }
+}
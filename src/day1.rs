@@ -1,23 +1,393 @@
+use crate::error::{ParseError, Span};
+use crate::solution::{Solution, Streaming, Windowed};
+use crate::stream::StreamError;
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::num::ParseIntError;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::BufRead;
+
+/// Day 1: Sonar Sweep
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+    type Input = Vec<i64>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input_day1(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
+impl Streaming for Day1 {
+    type Summary = Increases;
+
+    fn summarize(reader: impl BufRead) -> Result<Self::Summary, StreamError> {
+        summarize(reader)
+    }
+
+    fn summary_part1(summary: &Self::Summary) -> Self::Answer1 {
+        summary.measurements
+    }
+
+    fn summary_part2(summary: &Self::Summary) -> Self::Answer2 {
+        summary.windows
+    }
+}
+
+impl Windowed for Day1 {
+    fn part2_window(input: &Self::Input, size: usize) -> Self::Answer2 {
+        count_increases(input, size)
+    }
+}
+
+/// The number of times the depth increases, counted while reading the measurements.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Increases {
+    /// Increases between single measurements.
+    pub measurements: usize,
+    /// Increases between sums of three measurements.
+    pub windows: usize,
+}
+
+/// Counts the increases one measurement at a time, keeping only the last three measurements.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SonarCounter {
+    /// The last three measurements, oldest first.
+    window: [Option<i64>; 3],
+    increases: Increases,
+}
+
+impl SonarCounter {
+    /// Adds the next measurement, and returns the increases so far.
+    pub fn push(&mut self, measurement: i64) -> Increases {
+        if self.window[2].is_some_and(|previous| measurement > previous) {
+            self.increases.measurements += 1;
+        }
+        // Consecutive windows share two measurements, so comparing the sums is the same as
+        // comparing the measurement that enters the window with the one that leaves it.
+        if self.window[0].is_some_and(|leaving| measurement > leaving) {
+            self.increases.windows += 1;
+        }
+        self.window = [self.window[1], self.window[2], Some(measurement)];
+        self.increases
+    }
+
+    /// Returns the increases of the measurements so far.
+    pub const fn increases(&self) -> Increases {
+        self.increases
+    }
+}
+
+/// Statistics of the depth measurements, for analysing a sonar sweep beyond the increases.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct DepthProfile {
+    /// The number of measurements.
+    pub measurements: usize,
+    /// The number of steps between consecutive measurements where the depth increases.
+    pub increases: usize,
+    /// The number of steps where the depth decreases.
+    pub decreases: usize,
+    /// The number of steps where the depth stays the same.
+    pub flat: usize,
+    /// The longest run of steps in the same direction, or `None` if the depth never changes. The
+    /// first run wins a tie.
+    pub longest_run: Option<Run>,
+    /// The largest decrease in a single step, or `None` if the depth never decreases.
+    pub max_drop: Option<u64>,
+    /// The smallest depth.
+    pub min: i64,
+    /// The largest depth.
+    pub max: i64,
+    /// The mean depth, summed in 128 bits so that it cannot overflow.
+    pub mean: f64,
+}
+
+/// A run of consecutive measurements that all increase, or all decrease.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Run {
+    /// The index of the first measurement of the run.
+    pub start: usize,
+    /// The number of measurements in the run, including the first.
+    pub length: usize,
+    pub trend: Trend,
+}
+
+/// The direction in which the depth changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Trend {
+    Increasing,
+    Decreasing,
+}
+
+impl fmt::Display for Trend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Increasing => write!(f, "increasing"),
+            Self::Decreasing => write!(f, "decreasing"),
+        }
+    }
+}
+
+impl DepthProfile {
+    /// Calculates the profile of the measurements, or returns `None` if there are none.
+    pub fn new(measurements: &[i64]) -> Option<Self> {
+        let mut profile = Self {
+            measurements: measurements.len(),
+            increases: 0,
+            decreases: 0,
+            flat: 0,
+            longest_run: None,
+            max_drop: None,
+            min: *measurements.iter().min()?,
+            max: *measurements.iter().max()?,
+            mean: measurements.iter().map(|&m| m as i128).sum::<i128>() as f64
+                / measurements.len() as f64,
+        };
+        let mut run: Option<Run> = None;
+        for (i, step) in measurements.windows(2).enumerate() {
+            let trend = match step[1].cmp(&step[0]) {
+                Ordering::Greater => {
+                    profile.increases += 1;
+                    Some(Trend::Increasing)
+                }
+                Ordering::Less => {
+                    profile.decreases += 1;
+                    profile.max_drop = profile.max_drop.max(Some(step[0].abs_diff(step[1])));
+                    Some(Trend::Decreasing)
+                }
+                Ordering::Equal => {
+                    profile.flat += 1;
+                    None
+                }
+            };
+            run = match (run, trend) {
+                (Some(run), Some(trend)) if run.trend == trend => Some(Run {
+                    length: run.length + 1,
+                    ..run
+                }),
+                (_, trend) => trend.map(|trend| Run {
+                    start: i,
+                    length: 2,
+                    trend,
+                }),
+            };
+            if let Some(run) = run {
+                if profile
+                    .longest_run
+                    .is_none_or(|longest| run.length > longest.length)
+                {
+                    profile.longest_run = Some(run);
+                }
+            }
+        }
+        Some(profile)
+    }
+}
+
+impl fmt::Display for DepthProfile {
+    /// Formats the profile as a table with a row per statistic.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let longest_run = match self.longest_run {
+            Some(run) => format!("{} {} from index {}", run.length, run.trend, run.start),
+            None => String::from("-"),
+        };
+        let max_drop = self.max_drop.map_or(String::from("-"), |d| d.to_string());
+        let rows = [
+            ("Measurements", self.measurements.to_string()),
+            ("Increases", self.increases.to_string()),
+            ("Decreases", self.decreases.to_string()),
+            ("Flat", self.flat.to_string()),
+            ("Longest run", longest_run),
+            ("Max drop", max_drop),
+            ("Min depth", self.min.to_string()),
+            ("Max depth", self.max.to_string()),
+            ("Mean depth", format!("{:.1}", self.mean)),
+        ];
+        for (name, value) in rows {
+            writeln!(f, "{:<12}  {}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds glitches in the measurements with a Hampel filter, before counting the increases.
+///
+/// A measurement is an outlier if it differs from the median of its neighbourhood by more than
+/// `threshold` standard deviations, and by more than `min_deviation`. The standard deviation is
+/// estimated from the median absolute deviation, so the outliers themselves hardly affect it. The
+/// neighbourhood is centred on the measurement, so a slope or a step to a new depth is not
+/// discarded.
+///
+/// A neighbourhood of a few measurements often has a median absolute deviation of 0 or close to
+/// it, which turns every small change in depth on a flat stretch into an outlier. The minimum
+/// deviation puts a floor under the threshold for these; it is 0 by default, so only the threshold
+/// decides.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OutlierFilter {
+    /// The number of measurements on either side that form the neighbourhood of a measurement.
+    pub radius: usize,
+    /// The number of standard deviations a measurement may differ from the median.
+    pub threshold: f64,
+    /// The difference from the median that a measurement is always allowed, in the unit of the
+    /// measurements. Set it to the size of the normal changes in depth to keep them where the
+    /// median absolute deviation is 0.
+    pub min_deviation: f64,
+}
+
+impl Default for OutlierFilter {
+    fn default() -> Self {
+        Self {
+            radius: 3,
+            threshold: 3.0,
+            min_deviation: 0.0,
+        }
+    }
+}
+
+/// The ratio between the standard deviation and the median absolute deviation of normally
+/// distributed values.
+const MAD_TO_STD_DEV: f64 = 1.4826;
+
+impl OutlierFilter {
+    /// Returns the indices of the outliers in the measurements, in order.
+    pub fn outliers(&self, measurements: &[i64]) -> Vec<usize> {
+        (0..measurements.len())
+            .filter(|&i| {
+                let start = i.saturating_sub(self.radius);
+                let end = (i + self.radius + 1).min(measurements.len());
+                let neighbourhood: Vec<f64> =
+                    measurements[start..end].iter().map(|&m| m as f64).collect();
+                let center = median(neighbourhood.clone());
+                let deviation = median(neighbourhood.iter().map(|m| (m - center).abs()).collect());
+                let allowed = (self.threshold * MAD_TO_STD_DEV * deviation).max(self.min_deviation);
+                (measurements[i] as f64 - center).abs() > allowed
+            })
+            .collect()
+    }
+
+    /// Counts the increases in the measurements before and after discarding the outliers.
+    pub fn clean(&self, measurements: &[i64]) -> Outliers {
+        let discarded = self.outliers(measurements);
+        let mut outliers = discarded.iter().peekable();
+        let cleaned: Vec<i64> = measurements
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| outliers.next_if_eq(&i).is_none())
+            .map(|(_, &m)| m)
+            .collect();
+        Outliers {
+            raw_increases: part1(measurements),
+            cleaned_increases: part1(&cleaned),
+            discarded,
+        }
+    }
+}
+
+/// The increases in the measurements with and without the outliers found by an `OutlierFilter`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Outliers {
+    pub raw_increases: usize,
+    pub cleaned_increases: usize,
+    /// The indices of the discarded measurements.
+    pub discarded: Vec<usize>,
+}
+
+impl fmt::Display for Outliers {
+    /// Formats the increase counts as a table, in the same layout as a `DepthProfile`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let discarded = if self.discarded.is_empty() {
+            String::from("-")
+        } else {
+            self.discarded
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        writeln!(f, "{:<12}  {}", "Raw count", self.raw_increases)?;
+        writeln!(f, "{:<12}  {}", "Clean count", self.cleaned_increases)?;
+        writeln!(f, "{:<12}  {}", "Discarded", discarded)
+    }
+}
+
+/// Returns the median of a non-empty list of values.
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_unstable_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
 
 #[aoc_generator(day1)]
-fn parse_input_day1(input: &str) -> Result<Vec<i32>, ParseIntError> {
-    input.lines().map(|l| l.parse()).collect()
+pub fn parse_input_day1(input: &str) -> Result<Vec<i64>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| Span::line(1, i, l).parse())
+        .collect()
 }
 
 #[aoc(day1, part1)]
-fn part1(measurements: &[i32]) -> usize {
-    measurements.windows(2).filter(|w| w[1] > w[0]).count()
+pub fn part1(measurements: &[i64]) -> usize {
+    count_increases(measurements, 1)
 }
 
 #[aoc(day1, part2)]
-fn part2(measurements: &[i32]) -> usize {
-    let moving_average: Vec<i32> = measurements
-        .windows(3)
-        .map(|w| w[0] + w[1] + w[2])
-        .collect();
-    part1(&moving_average)
+pub fn part2(measurements: &[i64]) -> usize {
+    count_increases(measurements, 3)
+}
+
+/// Counts how often the sum of a window of `window` consecutive measurements is larger than the
+/// sum of the previous window.
+///
+/// Consecutive windows share all but one measurement, so the sum only increases if the measurement
+/// that enters the window is larger than the one that leaves it. This takes linear time for any
+/// window size, and never computes a sum, so it works for any type of measurement and cannot
+/// overflow. This is why there are no checked window sums: the answer is exact even for windows
+/// whose sums do not fit in an `i64`, so there is no overflow to report.
+pub fn count_increases<T: PartialOrd>(measurements: &[T], window: usize) -> usize {
+    measurements
+        .iter()
+        .zip(measurements.iter().skip(window))
+        .filter(|(leaving, entering)| entering > leaving)
+        .count()
+}
+
+/// Counts the increases while reading the measurements, keeping only the last three in memory.
+pub fn summarize(reader: impl BufRead) -> Result<Increases, StreamError> {
+    follow(reader, |_| {})
+}
+
+/// Counts the increases while reading the measurements, and calls `progress` with the running
+/// counts after every measurement.
+///
+/// A line is only read once it is complete, so this can follow an input that is still being
+/// written, e.g. the output of `tail -f`.
+pub fn follow(
+    reader: impl BufRead,
+    mut progress: impl FnMut(Increases),
+) -> Result<Increases, StreamError> {
+    let mut counter = SonarCounter::default();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        progress(counter.push(Span::line(1, i, &line).parse()?));
+    }
+    Ok(counter.increases())
 }
 
 #[cfg(test)]
@@ -25,18 +395,169 @@ mod tests {
     use super::*;
 
     #[test]
-    fn part1_example() {
+    fn test_parse_input() {
+        assert_eq!(Ok(vec![199, 200, 208]), parse_input_day1("199\n200\n208\n"));
+        assert_eq!(
+            Ok(vec![-5_000_000_000, 9_223_372_036_854_775_807]),
+            parse_input_day1("-5000000000\n9223372036854775807\n")
+        );
         assert_eq!(
-            part1(&[199, 200, 208, 210, 200, 207, 240, 269, 260, 263]),
-            7
+            Err(ParseError::InvalidNumber {
+                day: 1,
+                line: 2,
+                column: 1,
+                text: String::from("20O"),
+            }),
+            parse_input_day1("199\n20O\n208\n")
         );
     }
 
     #[test]
-    fn part2_example() {
+    fn test_count_increases() {
+        let measurements = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+        assert_eq!(7, count_increases(&measurements, 1));
+        assert_eq!(5, count_increases(&measurements, 3));
+        // 1017, 1025, 1065, 1126, 1176, 1239
+        assert_eq!(5, count_increases(&measurements, 5));
+        assert_eq!(0, count_increases(&measurements, 10));
+        assert_eq!(0, count_increases(&measurements, 100));
+        assert_eq!(0, count_increases::<i64>(&[], 3));
+
+        // The sum of the second window does not fit in an `i64`, and would wrap around to
+        // `i64::MIN`, but the sums are never calculated.
+        let large = [-1, i64::MAX, 0, 1];
+        assert!(large[1..]
+            .iter()
+            .try_fold(0_i64, |sum, &m| sum.checked_add(m))
+            .is_none());
+        assert_eq!(2, count_increases(&large, 1));
+        assert_eq!(1, count_increases(&large, 3));
+    }
+
+    #[test]
+    fn test_depth_profile() {
+        let measurements = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+        let profile = DepthProfile::new(&measurements).unwrap();
+        assert_eq!(
+            DepthProfile {
+                measurements: 10,
+                increases: 7,
+                decreases: 2,
+                flat: 0,
+                longest_run: Some(Run {
+                    start: 0,
+                    length: 4,
+                    trend: Trend::Increasing,
+                }),
+                max_drop: Some(10),
+                min: 199,
+                max: 269,
+                mean: 225.6,
+            },
+            profile
+        );
+        let table = profile.to_string();
+        assert!(table.contains("Longest run   4 increasing from index 0\n"));
+        assert!(table.contains("Mean depth    225.6\n"));
+        let json: serde_json::Value = serde_json::to_value(profile).unwrap();
+        assert_eq!("increasing", json["longest_run"]["trend"]);
+
+        let profile = DepthProfile::new(&[5, 5, 3, 1, 1]).unwrap();
+        assert_eq!(
+            (0, 2, 2),
+            (profile.increases, profile.decreases, profile.flat)
+        );
+        assert_eq!(Some(2), profile.max_drop);
+        assert_eq!(Some(3), profile.longest_run.map(|run| run.length));
+        assert_eq!(None, DepthProfile::new(&[7]).unwrap().longest_run);
+        assert_eq!(None, DepthProfile::new(&[]));
+    }
+
+    #[test]
+    fn test_outlier_filter() {
+        let filter = OutlierFilter::default();
+        let measurements = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+        assert!(filter.outliers(&measurements).is_empty());
+
+        for glitch in [0, 2000] {
+            let mut measurements = measurements;
+            measurements[4] = glitch;
+            let outliers = filter.clean(&measurements);
+            assert_eq!(vec![4], outliers.discarded);
+            assert_eq!(part1(&measurements), outliers.raw_increases);
+            assert_eq!(6, outliers.cleaned_increases);
+        }
+
+        // Slopes and steps to a new depth are real.
+        let slope: Vec<i64> = (100..120).collect();
+        assert!(filter.outliers(&slope).is_empty());
+        let step = [100, 100, 100, 100, 100, 200, 200, 200, 200, 200];
+        assert!(filter.outliers(&step).is_empty());
+        assert!(filter.outliers(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_outlier_filter_spike() {
+        // The depth changes by up to 6 between readings, with a spike of 28 in the middle.
+        let noisy = [100, 104, 98, 103, 97, 102, 130, 99, 96, 102, 104, 98, 103];
+        assert_eq!(vec![6], OutlierFilter::default().outliers(&noisy));
+        let lenient = OutlierFilter {
+            threshold: 10.0,
+            ..OutlierFilter::default()
+        };
+        assert!(lenient.outliers(&noisy).is_empty());
+
+        // A small change on a plateau has a median absolute deviation of 0 around it, so it is
+        // only kept with a minimum deviation.
+        let plateau = [100, 100, 100, 101, 100, 100, 100];
+        assert_eq!(vec![3], OutlierFilter::default().outliers(&plateau));
+        let floor = OutlierFilter {
+            min_deviation: 1.0,
+            ..OutlierFilter::default()
+        };
+        assert!(floor.outliers(&plateau).is_empty());
+    }
+
+    #[test]
+    fn test_sonar_counter() {
+        let mut counter = SonarCounter::default();
+        let running: Vec<(usize, usize)> = [199, 200, 208, 210, 200, 207]
+            .into_iter()
+            .map(|m| counter.push(m))
+            .map(|i| (i.measurements, i.windows))
+            .collect();
         assert_eq!(
-            part2(&[199, 200, 208, 210, 200, 207, 240, 269, 260, 263]),
-            5
+            vec![(0, 0), (1, 0), (2, 0), (3, 1), (3, 1), (4, 1)],
+            running
         );
+        assert_eq!(
+            Increases {
+                measurements: 4,
+                windows: 1
+            },
+            counter.increases()
+        );
+
+        let mut progress = vec![];
+        let increases = follow("199\n200\n".as_bytes(), |i| progress.push(i)).unwrap();
+        assert_eq!(2, progress.len());
+        assert_eq!(increases, progress[1]);
+    }
+
+    #[test]
+    fn summarize_example() {
+        let input = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+        let expected = Increases {
+            measurements: 7,
+            windows: 5,
+        };
+        assert_eq!(expected, summarize(input.as_bytes()).unwrap());
+        assert!(matches!(
+            summarize("199\n20O\n".as_bytes()),
+            Err(StreamError::Parse(ParseError::InvalidNumber {
+                line: 2,
+                ..
+            }))
+        ));
     }
 }
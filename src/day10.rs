@@ -1,87 +1,273 @@
+use crate::error::{ParseError, Span};
+use crate::solution::{Solution, Streaming, Trace};
+use crate::stream::StreamError;
+use crate::trace::{self, Terminal};
+use crate::viz;
 use aoc_runner_derive::{aoc, aoc_generator};
+use std::io::{self, BufRead};
+
+/// Day 10: Syntax Scoring
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    type Input = Vec<String>;
+    type Answer1 = usize;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
+impl Streaming for Day10 {
+    type Summary = Scores;
+
+    fn summarize(reader: impl BufRead) -> Result<Self::Summary, StreamError> {
+        summarize(reader)
+    }
+
+    fn summary_part1(summary: &Self::Summary) -> Self::Answer1 {
+        summary.syntax_error
+    }
+
+    fn summary_part2(summary: &Self::Summary) -> Self::Answer2 {
+        middle_score(&summary.completion)
+    }
+}
+
+impl Trace for Day10 {
+    fn trace(input: &Self::Input, terminal: &mut Terminal) -> io::Result<()> {
+        trace(input, terminal)
+    }
+}
+
+/// The scores of the navigation subsystem, calculated while reading it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Scores {
+    /// The total syntax error score of the corrupted lines.
+    pub syntax_error: usize,
+    /// The sorted completion scores of the incomplete lines. Only the scores are kept in memory,
+    /// not the lines, but the median needs a score for every incomplete line.
+    pub completion: Vec<u64>,
+}
 
 #[aoc_generator(day10)]
-fn parse_input(input: &str) -> Vec<String> {
-    input.lines().map(|l| l.to_string()).collect()
+pub fn parse_input(input: &str) -> Result<Vec<String>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| {
+            check_line(i, l)?;
+            Ok(l.to_string())
+        })
+        .collect()
+}
+
+/// Scores the lines while reading them.
+pub fn summarize(reader: impl BufRead) -> Result<Scores, StreamError> {
+    let mut scores = Scores::default();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        check_line(i, &line)?;
+        scores.syntax_error += syntax_error_score(&line);
+        scores.completion.extend(completion_score(&line, |_, _| {}));
+    }
+    scores.completion.sort_unstable();
+    Ok(scores)
+}
+
+/// Checks that a line only contains the characters that open and close chunks.
+fn check_line(i: usize, l: &str) -> Result<(), ParseError> {
+    match l.char_indices().find(|(_, c)| !"()[]{}<>".contains(*c)) {
+        Some((j, c)) => Err(Span::line(10, i, l).char_at(j, c).invalid_character()),
+        None => Ok(()),
+    }
 }
 
 #[aoc(day10, part1)]
-fn part1(lines: &[String]) -> usize {
-    let mut score = 0;
-    for line in lines {
-        let mut sequence = vec![];
-        for char in line.chars() {
-            match char {
-                '(' => sequence.push(')'),
-                '[' => sequence.push(']'),
-                '<' => sequence.push('>'),
-                '{' => sequence.push('}'),
-                _ => match sequence.pop() {
-                    None => continue,
-                    Some(expected) => {
-                        if char.eq(&expected) {
-                            continue;
-                        }
-                        match char {
-                            ')' => score += 3,
-                            ']' => score += 57,
-                            '}' => score += 1197,
-                            '>' => score += 25137,
-                            _ => unreachable!(),
-                        };
-                        break;
+pub fn part1(lines: &[String]) -> usize {
+    lines.iter().map(|line| syntax_error_score(line)).sum()
+}
+
+#[aoc(day10, part2)]
+pub fn part2(lines: &[String]) -> u64 {
+    let mut scores: Vec<u64> = lines
+        .iter()
+        .filter_map(|line| completion_score(line, |_, _| {}))
+        .collect();
+    scores.sort_unstable();
+    middle_score(&scores)
+}
+
+/// Returns the middle of the sorted completion scores.
+///
+/// # Panics
+///
+/// Panics if there are no scores, because every line is corrupted. The runner reports this as a
+/// failure of part 2.
+fn middle_score(scores: &[u64]) -> u64 {
+    *scores
+        .get(scores.len() / 2)
+        .expect("at least one line is not corrupted")
+}
+
+/// Returns the score of the first illegal character on a line, or 0 if the line is not corrupted.
+fn syntax_error_score(line: &str) -> usize {
+    let mut sequence = vec![];
+    for char in line.chars() {
+        match char {
+            '(' => sequence.push(')'),
+            '[' => sequence.push(']'),
+            '<' => sequence.push('>'),
+            '{' => sequence.push('}'),
+            _ => match sequence.pop() {
+                None => continue,
+                Some(expected) => {
+                    if char.eq(&expected) {
+                        continue;
                     }
-                },
-            }
+                    return match char {
+                        ')' => 3,
+                        ']' => 57,
+                        '}' => 1197,
+                        '>' => 25137,
+                        _ => unreachable!(),
+                    };
+                }
+            },
         }
     }
-    score
+    0
 }
 
-#[aoc(day10, part2)]
-fn part2(lines: &[String]) -> usize {
-    let mut scores = vec![];
-    'outer: for line in lines {
-        let mut sequence = vec![];
-        for char in line.chars() {
-            match char {
-                '(' => sequence.push(')'),
-                '[' => sequence.push(']'),
-                '<' => sequence.push('>'),
-                '{' => sequence.push('}'),
-                _ => match sequence.pop() {
-                    None => break,
-                    Some(expected) => {
-                        if char.eq(&expected) {
-                            continue;
-                        }
-                        continue 'outer;
+/// Returns the score of the characters that complete a line, or `None` if the line is corrupted.
+///
+/// The hook is called before every character with its index, and the closing characters that are
+/// expected at that point with the innermost chunk last.
+///
+/// # Panics
+///
+/// Panics if the score does not fit in a `u64`, which happens for lines with more than 28 open
+/// chunks.
+fn completion_score(line: &str, mut hook: impl FnMut(usize, &[char])) -> Option<u64> {
+    let mut sequence = vec![];
+    for (i, char) in line.chars().enumerate() {
+        hook(i, &sequence);
+        match char {
+            '(' => sequence.push(')'),
+            '[' => sequence.push(']'),
+            '<' => sequence.push('>'),
+            '{' => sequence.push('}'),
+            _ => match sequence.pop() {
+                None => break,
+                Some(expected) => {
+                    if char.eq(&expected) {
+                        continue;
                     }
-                },
-            }
+                    return None;
+                }
+            },
         }
-        let mut score = 0;
-        sequence.reverse();
-        for char in sequence {
-            score *= 5;
-            match char {
-                ')' => score += 1,
-                ']' => score += 2,
-                '}' => score += 3,
-                '>' => score += 4,
-                _ => unreachable!(),
+    }
+    let mut score = 0_u64;
+    sequence.reverse();
+    for char in sequence {
+        let value = match char {
+            ')' => 1,
+            ']' => 2,
+            '}' => 3,
+            '>' => 4,
+            _ => unreachable!(),
+        };
+        score = score
+            .checked_mul(5)
+            .and_then(|score| score.checked_add(value))
+            .expect("the completion score fits in a u64");
+    }
+    Some(score)
+}
+
+/// Prints the expected closing characters at every character of every line, followed by the
+/// outcome of the line, and finally the middle completion score.
+///
+/// # Panics
+///
+/// Panics like `part2()` if every line is corrupted.
+pub fn trace(lines: &[String], terminal: &mut Terminal) -> io::Result<()> {
+    let mut scores = vec![];
+    for (n, line) in lines.iter().enumerate() {
+        let mut result = Ok(());
+        let mut last = 0;
+        let score = completion_score(line, |i, expected| {
+            last = i;
+            if result.is_ok() {
+                let title = format!("Line {}, column {}", n + 1, i + 1);
+                let body = format!(
+                    "{}\nExpected: {}",
+                    highlight(line, i, viz::YELLOW),
+                    expected.iter().rev().collect::<String>()
+                );
+                result = terminal.frame(&title, &body);
+            }
+        });
+        result?;
+        match score {
+            Some(score) => {
+                let title = format!("Line {} is incomplete", n + 1);
+                let body = format!("{}\nScore: {}", line, score);
+                terminal.frame(&title, &body)?;
+                scores.push(score);
+            }
+            None => {
+                let title = format!("Line {} is corrupted", n + 1);
+                terminal.frame(&title, &highlight(line, last, viz::RED))?;
             }
         }
-        scores.push(score);
     }
     scores.sort_unstable();
-    scores[scores.len() / 2]
+    let middle = middle_score(&scores);
+    let body = scores
+        .iter()
+        .map(|&score| {
+            if score == middle {
+                trace::bold(score)
+            } else {
+                score.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    terminal.frame(&format!("Middle score: {}", middle), &body)
+}
+
+/// Returns the line with the character at the given index highlighted, and the characters before
+/// it dimmed.
+fn highlight(line: &str, index: usize, color: viz::Color) -> String {
+    // Lines only contain brackets, so the index of a character is also its byte offset.
+    let (before, after) = line.split_at(index);
+    let mut chars = after.chars();
+    let current = chars.next().map(|c| trace::bg(trace::bold(c), color));
+    format!(
+        "{}{}{}",
+        trace::dim(before),
+        current.unwrap_or_default(),
+        chars.as_str()
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use indoc::indoc;
+    use crate::example;
 
     #[test]
     fn test_parse_input() {
@@ -97,32 +283,68 @@ mod tests {
             String::from("<{([([[(<>()){}]>(<<{{"),
             String::from("<{([{{}}[<[[[<>{}]]]>[]]"),
         ];
-        assert_eq!(expected, parse_input(get_test_input()));
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
+    }
+
+    #[test]
+    fn test_parse_input_error() {
+        assert_eq!(
+            Err(ParseError::InvalidCharacter {
+                day: 10,
+                line: 1,
+                column: 5,
+                text: String::from("|"),
+            }),
+            parse_input("[({(|(())[]>[[{[]{<()<>>\n")
+        );
+    }
+
+    #[test]
+    fn summarize_example() {
+        let scores = summarize(get_test_input().as_bytes()).unwrap();
+        assert_eq!(26397, Day10::summary_part1(&scores));
+        assert_eq!(288957, Day10::summary_part2(&scores));
+        assert_eq!(5, scores.completion.len());
     }
+
+    #[test]
+    fn test_part2_input() {
+        // The scores do not fit in a 32-bit `usize`, as on WebAssembly.
+        let lines = parse_input(include_str!("../input/2021/day10.txt")).unwrap();
+        let max = lines.iter().filter_map(|l| completion_score(l, |_, _| {}));
+        assert_eq!(Some(30_269_419_813), max.max());
+        assert_eq!(2_904_180_541, part2(&lines));
+    }
+
+    #[test]
+    fn test_completion_score() {
+        // The score of 28 open chunks is (5^28 - 1) / 4, which still fits in a `u64`.
+        let score = completion_score(&"(".repeat(28), |_, _| {});
+        assert_eq!(Some(9_313_225_746_154_785_156), score);
+    }
+
     #[test]
-    fn part1_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(26397, part1(&input));
+    #[should_panic(expected = "the completion score fits in a u64")]
+    fn test_completion_score_overflow() {
+        completion_score(&"(".repeat(29), |_, _| {});
     }
 
     #[test]
-    fn part2_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(288957, part2(&input));
+    fn test_trace() {
+        let mut out = vec![];
+        let mut terminal = Terminal::new(&mut out, None);
+        let lines = parse_input("[({(<(())[]>[[{[]{<()<>>\n{([(<{}[<>[]}>{[]{[(<()>\n").unwrap();
+        trace(&lines, &mut terminal).unwrap();
+        // The first line is incomplete, the second line is corrupted at the 13th character.
+        assert_eq!(24 + 1 + 13 + 1 + 1, terminal.frames());
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Expected: }}]])})]"));
+        assert!(out.contains("Score: 288957"));
+        assert!(out.contains("Line 2 is corrupted"));
+        assert!(out.contains("Middle score: 288957"));
     }
 
     fn get_test_input<'a>() -> &'a str {
-        indoc! {"
-            [({(<(())[]>[[{[]{<()<>>
-            [(()[<>])]({[<{<<[]>>(
-            {([(<{}[<>[]}>{[]{[(<()>
-            (((({<>}<{<{<>}{[]{[]{}
-            [[<[([]))<([[{}[[()]]]
-            [{[{({}]{}}([{[{{{}}([]
-            {<[[]]>}<{[{[{[]{()[[[]
-            [<(<(<(<{}))><([]([]()
-            <{([([[(<>()){}]>(<<{{
-            <{([{{}}[<[[[<>{}]]]>[]]
-        "}
+        example::input(include_str!("../examples/day10/example.txt"))
     }
 }
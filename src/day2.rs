@@ -1,33 +1,139 @@
+use crate::error::{ParseError, Span};
+use crate::solution::{Solution, Streaming};
+use crate::stream::StreamError;
 use aoc_runner_derive::{aoc, aoc_generator};
 use regex::Regex;
+use std::io::BufRead;
 use Direction::*;
 
 #[derive(Debug, PartialEq)]
-enum Direction {
+pub enum Direction {
     Forward(isize),
     Down(isize),
     Up(isize),
 }
 
+/// Day 2: Dive!
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+    type Input = Vec<Direction>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
+impl Streaming for Day2 {
+    type Summary = Course;
+
+    fn summarize(reader: impl BufRead) -> Result<Self::Summary, StreamError> {
+        summarize(reader)
+    }
+
+    fn summary_part1(summary: &Self::Summary) -> Self::Answer1 {
+        // Without aim, the commands that change the aim change the depth instead.
+        (summary.aim as usize)
+            .checked_mul(summary.horizontal as usize)
+            .expect("the answer fits in a usize")
+    }
+
+    fn summary_part2(summary: &Self::Summary) -> Self::Answer2 {
+        (summary.depth as usize)
+            .checked_mul(summary.horizontal as usize)
+            .expect("the answer fits in a usize")
+    }
+}
+
+/// The final position of the submarine, as calculated while reading the commands.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Course {
+    pub horizontal: isize,
+    pub aim: isize,
+    pub depth: isize,
+}
+
+impl Course {
+    /// Returns the position after following a command, or `None` if it overflows.
+    fn follow(self, command: &Direction) -> Option<Self> {
+        Some(match *command {
+            Forward(v) => Self {
+                horizontal: self.horizontal.checked_add(v)?,
+                depth: self.depth.checked_add(v.checked_mul(self.aim)?)?,
+                ..self
+            },
+            Down(v) => Self {
+                aim: self.aim.checked_add(v)?,
+                ..self
+            },
+            Up(v) => Self {
+                aim: self.aim.checked_sub(v)?,
+                ..self
+            },
+        })
+    }
+}
+
 #[aoc_generator(day2)]
-fn parse_input(input: &str) -> Vec<Direction> {
-    let re = Regex::new(r"^([a-z]+) (\d+)$").unwrap();
+pub fn parse_input(input: &str) -> Result<Vec<Direction>, ParseError> {
+    let re = command_regex();
     input
         .lines()
-        .map(|l| {
-            let caps = re.captures(l).unwrap();
-            let amount: isize = caps[2].parse().unwrap();
-            match &caps[1] {
-                "forward" => Forward(amount),
-                "down" => Down(amount),
-                _ => Up(amount),
-            }
-        })
+        .enumerate()
+        .map(|(i, l)| parse_command(&re, i, l))
         .collect()
 }
 
+/// Follows the commands while reading them, without keeping them in memory.
+///
+/// # Panics
+///
+/// Panics if the position does not fit in an `isize`. The arithmetic is checked, since a large
+/// input could otherwise wrap around silently in release builds.
+pub fn summarize(reader: impl BufRead) -> Result<Course, StreamError> {
+    let re = command_regex();
+    let mut course = Course::default();
+    for (i, line) in reader.lines().enumerate() {
+        let command = parse_command(&re, i, &line?)?;
+        course = course
+            .follow(&command)
+            .expect("the position fits in an isize");
+    }
+    Ok(course)
+}
+
+fn command_regex() -> Regex {
+    Regex::new(r"^([a-z]+) (\d+)$").unwrap()
+}
+
+/// Parses the command on the line with the given index.
+fn parse_command(re: &Regex, i: usize, l: &str) -> Result<Direction, ParseError> {
+    let caps = re
+        .captures(l)
+        .ok_or_else(|| Span::line(2, i, l).invalid_format())?;
+    let command = caps.get(1).unwrap().as_str();
+    let amount: isize = Span::new(2, i, l, caps.get(2).unwrap().as_str()).parse()?;
+    match command {
+        "forward" => Ok(Forward(amount)),
+        "down" => Ok(Down(amount)),
+        "up" => Ok(Up(amount)),
+        _ => Err(Span::new(2, i, l, command).invalid_format()),
+    }
+}
+
 #[aoc(day2, part1)]
-fn part1(commands: &[Direction]) -> usize {
+pub fn part1(commands: &[Direction]) -> usize {
     let mut depth = 0;
     let mut horpos = 0;
     for command in commands {
@@ -41,7 +147,7 @@ fn part1(commands: &[Direction]) -> usize {
 }
 
 #[aoc(day2, part2)]
-fn part2(commands: &[Direction]) -> usize {
+pub fn part2(commands: &[Direction]) -> usize {
     let mut aim = 0;
     let mut depth = 0;
     let mut horpos = 0;
@@ -62,33 +168,51 @@ fn part2(commands: &[Direction]) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use indoc::indoc;
+    use crate::example;
 
     #[test]
     fn test_parse_input() {
         let expected = vec![Forward(5), Down(5), Forward(8), Up(3), Down(8), Forward(2)];
-        assert_eq!(expected, parse_input(get_test_input()));
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
     }
+
+    #[test]
+    fn test_parse_input_error() {
+        assert_eq!(
+            Err(ParseError::InvalidFormat {
+                day: 2,
+                line: 2,
+                column: 1,
+                text: String::from("backward"),
+            }),
+            parse_input("forward 5\nbackward 3\n")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidFormat {
+                day: 2,
+                line: 1,
+                column: 1,
+                text: String::from("forward -5"),
+            }),
+            parse_input("forward -5\n")
+        );
+    }
+
     #[test]
-    fn part1_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(150, part1(&input));
+    fn summarize_example() {
+        let course = summarize(get_test_input().as_bytes()).unwrap();
+        assert_eq!(150, Day2::summary_part1(&course));
+        assert_eq!(900, Day2::summary_part2(&course));
     }
 
     #[test]
-    fn part2_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(900, part2(&input));
+    #[should_panic(expected = "the position fits in an isize")]
+    fn summarize_overflow() {
+        let input = format!("down {}\nforward 2\n", isize::MAX);
+        summarize(input.as_bytes()).unwrap();
     }
 
     fn get_test_input<'a>() -> &'a str {
-        indoc! {"
-           forward 5
-           down 5
-           forward 8
-           up 3
-           down 8
-           forward 2
-        "}
+        example::input(include_str!("../examples/day2/example.txt"))
     }
 }
@@ -1,13 +1,174 @@
+use crate::error::{end_of_input, ParseError, Span};
+use crate::solution::{Solution, Streaming};
+use crate::stream::StreamError;
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::io::BufRead;
+
+/// Day 3: Binary Diagnostic
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+    type Input = Vec<Vec<char>>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
+impl Streaming for Day3 {
+    type Summary = Histogram;
+
+    fn summarize(reader: impl BufRead) -> Result<Self::Summary, StreamError> {
+        summarize(reader)
+    }
+
+    fn summary_part1(summary: &Self::Summary) -> Self::Answer1 {
+        summary_part1(summary)
+    }
+
+    fn summary_part2(summary: &Self::Summary) -> Self::Answer2 {
+        summary_part2(summary)
+    }
+}
+
+/// The number of times every number occurs in a diagnostic report.
+///
+/// This takes memory for every distinct number instead of every line, so it stays small for
+/// reports with many lines of a limited number of bits.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Histogram {
+    /// The number of bits of every number.
+    pub bits: usize,
+    pub counts: BTreeMap<u64, usize>,
+}
+
+impl Histogram {
+    /// Returns the number of numbers in the given range.
+    fn count(&self, start: u64, end: u128) -> usize {
+        self.counts
+            .range(start..)
+            .take_while(|(n, _)| u128::from(**n) < end)
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+/// The largest number of bits in a number. The answers multiply two numbers, so they always fit
+/// in a `u64`.
+const MAX_BITS: usize = 32;
 
 #[aoc_generator(day3)]
-fn parse_input(input: &str) -> Vec<Vec<char>> {
-    input.lines().map(|l| l.chars().collect()).collect()
+pub fn parse_input(input: &str) -> Result<Vec<Vec<char>>, ParseError> {
+    let mut report: Vec<Vec<char>> = vec![];
+    for (i, l) in input.lines().enumerate() {
+        check_line(i, l, report.first().map(Vec::len))?;
+        report.push(l.chars().collect());
+    }
+    if report.is_empty() {
+        return Err(end_of_input(3, input));
+    }
+    Ok(report)
+}
+
+/// Checks that a line is a binary number with the same number of bits as the previous lines, and
+/// at most `MAX_BITS` bits.
+fn check_line(i: usize, l: &str, bits: Option<usize>) -> Result<(), ParseError> {
+    let span = Span::line(3, i, l);
+    if let Some((j, c)) = l.char_indices().find(|(_, c)| *c != '0' && *c != '1') {
+        return Err(span.char_at(j, c).invalid_character());
+    }
+    if l.len() > MAX_BITS {
+        return Err(span.out_of_range());
+    }
+    // All numbers in the report should have the same number of bits.
+    if l.is_empty() || bits.is_some_and(|bits| bits != l.len()) {
+        return Err(span.invalid_format());
+    }
+    Ok(())
+}
+
+/// Counts the numbers in the report while reading it.
+pub fn summarize(reader: impl BufRead) -> Result<Histogram, StreamError> {
+    let mut histogram = Histogram::default();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        check_line(i, &line, (i > 0).then_some(histogram.bits))?;
+        let number = u64::from_str_radix(&line, 2).expect("the line is a binary number");
+        histogram.bits = line.len();
+        *histogram.counts.entry(number).or_insert(0) += 1;
+    }
+    if histogram.counts.is_empty() {
+        return Err(end_of_input(3, "").into());
+    }
+    Ok(histogram)
+}
+
+/// Solves part 1 for a histogram of the report.
+pub fn summary_part1(histogram: &Histogram) -> u64 {
+    let total: usize = histogram.counts.values().sum();
+    let mut gamma = 0;
+    for bit in (0..histogram.bits).rev() {
+        let zeros: usize = histogram
+            .counts
+            .iter()
+            .filter(|(n, _)| *n >> bit & 1 == 0)
+            .map(|(_, count)| count)
+            .sum();
+        gamma = gamma << 1 | u64::from(zeros <= total / 2);
+    }
+    let bitmask = 2_u64.pow(histogram.bits as u32) - 1;
+    gamma * (bitmask & !gamma)
+}
+
+/// Solves part 2 for a histogram of the report.
+pub fn summary_part2(histogram: &Histogram) -> u64 {
+    rating(histogram, true) * rating(histogram, false)
+}
+
+/// Returns the oxygen generator rating if `most_common` is set, or the CO2 scrubber rating.
+///
+/// The numbers that are left after filtering on the first bits form a range, which is halved for
+/// every bit.
+fn rating(histogram: &Histogram, most_common: bool) -> u64 {
+    let (mut start, mut end) = (0_u64, 1_u128 << histogram.bits);
+    for bit in (0..histogram.bits).rev() {
+        let total = histogram.count(start, end);
+        if total == 1 {
+            break;
+        }
+        let middle = start + (1 << bit);
+        let zeros = histogram.count(start, u128::from(middle));
+        let ones = total - zeros;
+        if (zeros > ones) != most_common {
+            start = middle;
+        } else {
+            end = u128::from(middle);
+        }
+    }
+    let (number, _) = histogram
+        .counts
+        .range(start..)
+        .next()
+        .filter(|(n, _)| u128::from(**n) < end)
+        .expect("a number is left after filtering");
+    *number
 }
 
 #[aoc(day3, part1)]
-fn part1(report: &[Vec<char>]) -> usize {
+pub fn part1(report: &[Vec<char>]) -> u64 {
     let mcb: String = transpose(report.to_owned())
         .iter()
         .map(|vc| -> String {
@@ -18,13 +179,13 @@ fn part1(report: &[Vec<char>]) -> usize {
             .to_string()
         })
         .collect();
-    let intval = usize::from_str_radix(mcb.as_str(), 2).unwrap();
-    let bitmask = 2_usize.pow(mcb.len() as u32) - 1;
+    let intval = u64::from_str_radix(mcb.as_str(), 2).unwrap();
+    let bitmask = 2_u64.pow(mcb.len() as u32) - 1;
     intval * (bitmask & !intval)
 }
 
 #[aoc(day3, part2)]
-fn part2(report: &[Vec<char>]) -> usize {
+pub fn part2(report: &[Vec<char>]) -> u64 {
     let numsize = report[0].len();
 
     let mut oxygen = report.to_owned();
@@ -44,7 +205,7 @@ fn part2(report: &[Vec<char>]) -> usize {
         };
     }
     let oxygen = oxygen[0].iter().collect::<String>();
-    let oxygen = usize::from_str_radix(oxygen.as_str(), 2).unwrap();
+    let oxygen = u64::from_str_radix(oxygen.as_str(), 2).unwrap();
 
     let mut co2 = report.to_owned();
     for i in 0..numsize {
@@ -63,7 +224,7 @@ fn part2(report: &[Vec<char>]) -> usize {
         };
     }
     let co2 = co2[0].iter().collect::<String>();
-    let co2 = usize::from_str_radix(co2.as_str(), 2).unwrap();
+    let co2 = u64::from_str_radix(co2.as_str(), 2).unwrap();
 
     oxygen * co2
 }
@@ -90,7 +251,7 @@ fn filter(col: Vec<Vec<char>>, char: char, pos: usize) -> Vec<Vec<char>> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use indoc::indoc;
+    use crate::example;
 
     #[test]
     fn test_parse_input() {
@@ -108,34 +269,74 @@ mod tests {
             String::from("00010").chars().collect(),
             String::from("01010").chars().collect(),
         ];
-        assert_eq!(expected, parse_input(get_test_input()));
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
+    }
+
+    #[test]
+    fn test_parse_input_error() {
+        assert_eq!(
+            Err(ParseError::InvalidCharacter {
+                day: 3,
+                line: 2,
+                column: 3,
+                text: String::from("2"),
+            }),
+            parse_input("00100\n11210\n")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidFormat {
+                day: 3,
+                line: 2,
+                column: 1,
+                text: String::from("1110"),
+            }),
+            parse_input("00100\n1110\n")
+        );
+        let long = "1".repeat(33);
+        assert_eq!(
+            Err(ParseError::OutOfRange {
+                day: 3,
+                line: 1,
+                column: 1,
+                text: long.clone(),
+            }),
+            parse_input(&long)
+        );
+        assert!(matches!(
+            summarize(long.as_bytes()),
+            Err(StreamError::Parse(ParseError::OutOfRange { .. }))
+        ));
+        assert!(matches!(
+            summarize("".as_bytes()),
+            Err(StreamError::Parse(ParseError::UnexpectedEnd {
+                line: 1,
+                ..
+            }))
+        ));
     }
+
     #[test]
-    fn part1_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(198, part1(&input));
+    fn test_max_bits() {
+        // The answers do not fit in 32 bits.
+        let input = "10".repeat(16) + "\n" + &"10".repeat(16) + "\n" + &"01".repeat(16) + "\n";
+        let answer = 0xAAAA_AAAA * 0x5555_5555;
+        let report = parse_input(&input).unwrap();
+        assert_eq!(answer, part1(&report));
+        assert_eq!(answer, part2(&report));
+        let histogram = summarize(input.as_bytes()).unwrap();
+        assert_eq!(answer, summary_part1(&histogram));
+        assert_eq!(answer, summary_part2(&histogram));
     }
 
     #[test]
-    fn part2_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(230, part2(&input));
+    fn summarize_example() {
+        let histogram = summarize(get_test_input().as_bytes()).unwrap();
+        assert_eq!(5, histogram.bits);
+        assert_eq!(198, summary_part1(&histogram));
+        assert_eq!(230, summary_part2(&histogram));
     }
 
     fn get_test_input<'a>() -> &'a str {
-        indoc! {"
-            00100
-            11110
-            10110
-            10111
-            10101
-            01111
-            00111
-            11100
-            10000
-            11001
-            00010
-            01010
-        "}
+        example::input(include_str!("../examples/day3/example.txt"))
     }
 }
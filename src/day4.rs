@@ -1,21 +1,31 @@
+use crate::error::{end_of_input, parse_list, ParseError, Span};
+use crate::grid::Grid;
+use crate::solution::{Solution, Trace, Visualize};
+use crate::trace::{self, Terminal};
+use crate::viz::{self, Output};
 use aoc_runner_derive::{aoc, aoc_generator};
+use std::io;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-struct Board {
-    numbers: [[u8; 5]; 5],
-    marks: [[bool; 5]; 5],
+#[derive(Clone, Debug, PartialEq)]
+pub struct Board {
+    numbers: Grid<u8>,
+    marks: Grid<bool>,
     winner: bool,
 }
 
 impl Board {
+    fn new(numbers: Grid<u8>) -> Self {
+        let marks = Grid::new(numbers.width(), numbers.height(), false);
+        Self {
+            numbers,
+            marks,
+            winner: false,
+        }
+    }
+
     fn mark_number(&mut self, number: u8) {
-        'outer: for x in 0..5 {
-            for y in 0..5 {
-                if self.numbers[x][y] == number {
-                    self.marks[x][y] = true;
-                    break 'outer;
-                }
-            }
+        if let Some(position) = self.numbers.position(|n| *n == number) {
+            self.marks[position] = true;
         }
         if self.has_horizontal_line() || self.has_vertical_line() {
             self.winner = true;
@@ -27,95 +37,98 @@ impl Board {
     }
 
     fn has_horizontal_line(&self) -> bool {
-        for y in 0..5 {
-            let mut c = 0;
-            for x in 0..5 {
-                if self.marks[x][y] {
-                    c += 1;
-                }
-            }
-            if c == 5 {
-                return true;
-            }
-        }
-        false
+        self.marks.rows().any(|row| row.iter().all(|m| *m))
     }
 
     fn has_vertical_line(&self) -> bool {
-        for x in 0..5 {
-            let mut c = 0;
-            for y in 0..5 {
-                if self.marks[x][y] {
-                    c += 1;
-                }
-            }
-            if c == 5 {
-                return true;
-            }
-        }
-        false
+        self.marks.columns().any(|mut column| column.all(|m| *m))
     }
 
     fn get_unmarked_total(&self) -> usize {
-        let mut total = 0;
-        for x in 0..5 {
-            for y in 0..5 {
-                if !self.marks[x][y] {
-                    total += self.numbers[x][y] as usize;
-                }
-            }
-        }
-        total
+        self.numbers
+            .iter()
+            .zip(self.marks.iter())
+            .filter(|(_, m)| !**m)
+            .map(|(n, _)| *n as usize)
+            .sum()
+    }
+}
+
+/// Day 4: Giant Squid
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+    type Input = (Vec<u8>, Vec<Board>);
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
+impl Visualize for Day4 {
+    fn visualize(input: &Self::Input, output: &mut Output) -> io::Result<()> {
+        visualize(input, output)
+    }
+}
+
+impl Trace for Day4 {
+    fn trace(input: &Self::Input, terminal: &mut Terminal) -> io::Result<()> {
+        trace(input, terminal)
     }
 }
 
 #[aoc_generator(day4)]
-fn parse_input(input: &str) -> (Vec<u8>, Vec<Board>) {
-    let mut lines: Vec<&str> = input.lines().collect();
-    let values = lines
-        .drain(0..1)
-        .collect::<String>()
-        .split(',')
-        .map(|v| v.parse::<u8>().unwrap())
-        .collect();
-
-    // Skip the empty line between the values and boards.
-    lines.drain(0..1);
+pub fn parse_input(input: &str) -> Result<(Vec<u8>, Vec<Board>), ParseError> {
+    let mut lines = input.lines().enumerate();
+    let (i, line) = lines.next().ok_or_else(|| end_of_input(4, input))?;
+    let values = parse_list(4, i, line, ',')?;
 
     let mut boards = vec![];
-    let mut board = [[0_u8; 5]; 5];
-    let mut i = 0;
-
-    for line in lines {
-        if line.is_empty() {
-            // An empty line indicates that a new board will start. Add the completed board to the
-            // list, and initialize a new one.
-            boards.push(Board {
-                numbers: board,
-                marks: [[false; 5]; 5],
-                winner: false,
-            });
-            board = [[0_u8; 5]; 5];
-            i = 0;
-        } else {
-            let row = line
-                .split_whitespace()
-                .map(|x| x.parse::<u8>().unwrap())
-                .collect::<Vec<u8>>();
-            board[i][..5].clone_from_slice(&row[..5]);
-            i += 1;
+    let mut board = Vec::with_capacity(25);
+
+    for (i, line) in lines {
+        let span = Span::line(4, i, line);
+        if line.trim().is_empty() {
+            // Empty lines separate the values and the boards. They are not allowed halfway a board.
+            if !board.is_empty() {
+                return Err(span.unexpected_end());
+            }
+            continue;
+        }
+        let numbers = line
+            .split_whitespace()
+            .map(|x| Span::new(4, i, line, x).parse())
+            .collect::<Result<Vec<u8>, _>>()?;
+        if numbers.len() != 5 {
+            return Err(span.invalid_format());
+        }
+        board.extend(numbers);
+
+        // Add the completed board to the list, and start a new one.
+        if board.len() == 25 {
+            boards.push(Board::new(Grid::from_vec(5, board).unwrap()));
+            board = Vec::with_capacity(25);
         }
     }
-    boards.push(Board {
-        numbers: board,
-        marks: [[false; 5]; 5],
-        winner: false,
-    });
-    (values, boards)
+    if !board.is_empty() || boards.is_empty() {
+        return Err(end_of_input(4, input));
+    }
+    Ok((values, boards))
 }
 
 #[aoc(day4, part1)]
-fn part1(game: &(Vec<u8>, Vec<Board>)) -> usize {
+pub fn part1(game: &(Vec<u8>, Vec<Board>)) -> usize {
     let (numbers, mut boards) = game.clone();
     for number in numbers {
         for board in boards.iter_mut() {
@@ -129,7 +142,12 @@ fn part1(game: &(Vec<u8>, Vec<Board>)) -> usize {
 }
 
 #[aoc(day4, part2)]
-fn part2(game: &(Vec<u8>, Vec<Board>)) -> usize {
+pub fn part2(game: &(Vec<u8>, Vec<Board>)) -> usize {
+    last_winner(game, |_, _| {}).expect("every board wins")
+}
+
+/// Plays until the last board wins, and returns its score. The hook is called after every draw.
+fn last_winner(game: &(Vec<u8>, Vec<Board>), mut hook: impl FnMut(u8, &[Board])) -> Option<usize> {
     let (numbers, mut boards) = game.clone();
     let mut remaining_winners = boards.len();
     for number in numbers {
@@ -139,19 +157,113 @@ fn part2(game: &(Vec<u8>, Vec<Board>)) -> usize {
                 if board.is_winner() {
                     remaining_winners -= 1;
                     if remaining_winners == 0 {
-                        return number as usize * board.get_unmarked_total();
+                        let score = number as usize * board.get_unmarked_total();
+                        hook(number, &boards);
+                        return Some(score);
                     }
                 }
             }
         }
+        hook(number, &boards);
     }
-    unreachable!()
+    None
+}
+
+/// Renders the game as an animation, with a frame in `bingo-NNNN.ppm` before the first draw and
+/// after every draw until all boards have won.
+///
+/// Marked numbers are yellow, or green on boards that have won. The number that was just drawn is
+/// white.
+pub fn visualize(game: &(Vec<u8>, Vec<Board>), output: &mut Output) -> io::Result<()> {
+    let (numbers, mut boards) = game.clone();
+    output.write_frame("bingo.ppm", 0, render_boards(&boards, None))?;
+    for (i, number) in numbers.into_iter().enumerate() {
+        for board in boards.iter_mut().filter(|b| !b.is_winner()) {
+            board.mark_number(number);
+        }
+        output.write_frame("bingo.ppm", i + 1, render_boards(&boards, Some(number)))?;
+        if boards.iter().all(Board::is_winner) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Renders the boards side by side in a square layout, separated by a margin of one cell.
+fn render_boards(boards: &[Board], drawn: Option<u8>) -> Vec<u8> {
+    const SIZE: usize = 6;
+    let columns = (1..).find(|c| c * c >= boards.len()).unwrap();
+    let rows = boards.len().div_ceil(columns);
+    let mut image = Grid::new(columns * SIZE + 1, rows * SIZE + 1, viz::BLACK);
+    for (i, board) in boards.iter().enumerate() {
+        let (left, top) = (i % columns * SIZE + 1, i / columns * SIZE + 1);
+        for (x, y) in board.numbers.positions() {
+            let marked = board.marks[(x, y)];
+            image[(left + x, top + y)] = match (marked, board.is_winner()) {
+                (true, _) if drawn == Some(board.numbers[(x, y)]) => viz::WHITE,
+                (true, true) => viz::GREEN,
+                (true, false) => viz::YELLOW,
+                (false, _) => viz::GREY,
+            };
+        }
+    }
+    viz::ppm(&image, 8)
+}
+
+/// Prints the boards after every draw until the last board wins.
+///
+/// Marked numbers are highlighted, in green on boards that have won. The number that was just
+/// drawn is printed in bold.
+pub fn trace(game: &(Vec<u8>, Vec<Board>), terminal: &mut Terminal) -> io::Result<()> {
+    let mut result = Ok(());
+    let score = last_winner(game, |number, boards| {
+        if result.is_ok() {
+            let title = format!("Drew {}", number);
+            result = terminal.frame(&title, &render_text(boards, number));
+        }
+    });
+    result?;
+    match score {
+        Some(score) => terminal.frame("The last board has won", &format!("Score: {}", score)),
+        None => terminal.frame("Not every board wins", ""),
+    }
+}
+
+/// Renders the boards next to each other, wrapping after 5 boards.
+fn render_text(boards: &[Board], drawn: u8) -> String {
+    let mut text = String::new();
+    for row_of_boards in boards.chunks(5) {
+        for y in 0..5 {
+            for board in row_of_boards {
+                for x in 0..5 {
+                    let number = board.numbers[(x, y)];
+                    let mut cell = format!("{:>3}", number);
+                    if board.marks[(x, y)] {
+                        let color = if board.is_winner() {
+                            viz::GREEN
+                        } else {
+                            viz::YELLOW
+                        };
+                        cell = trace::fg(cell, color);
+                    }
+                    if number == drawn {
+                        cell = trace::bold(cell);
+                    }
+                    text.push_str(&cell);
+                }
+                text.push_str("    ");
+            }
+            text.push('\n');
+        }
+        text.push('\n');
+    }
+    text
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use indoc::indoc;
+    use crate::example;
 
     #[test]
     fn test_parse_input() {
@@ -161,76 +273,90 @@ mod tests {
                 19, 3, 26, 1,
             ],
             vec![
-                Board {
-                    numbers: [
-                        [22, 13, 17, 11, 0],
-                        [8, 2, 23, 4, 24],
-                        [21, 9, 14, 16, 7],
-                        [6, 10, 3, 18, 5],
-                        [1, 12, 20, 15, 19],
-                    ],
-                    marks: [[false; 5]; 5],
-                    winner: false,
-                },
-                Board {
-                    numbers: [
-                        [3, 15, 0, 2, 22],
-                        [9, 18, 13, 17, 5],
-                        [19, 8, 7, 25, 23],
-                        [20, 11, 10, 24, 4],
-                        [14, 21, 16, 12, 6],
-                    ],
-                    marks: [[false; 5]; 5],
-                    winner: false,
-                },
-                Board {
-                    numbers: [
-                        [14, 21, 17, 24, 4],
-                        [10, 16, 15, 9, 19],
-                        [18, 8, 23, 26, 20],
-                        [22, 11, 13, 6, 5],
-                        [2, 0, 12, 3, 7],
-                    ],
-                    marks: [[false; 5]; 5],
-                    winner: false,
-                },
+                Board::new(Grid::from([
+                    [22, 13, 17, 11, 0],
+                    [8, 2, 23, 4, 24],
+                    [21, 9, 14, 16, 7],
+                    [6, 10, 3, 18, 5],
+                    [1, 12, 20, 15, 19],
+                ])),
+                Board::new(Grid::from([
+                    [3, 15, 0, 2, 22],
+                    [9, 18, 13, 17, 5],
+                    [19, 8, 7, 25, 23],
+                    [20, 11, 10, 24, 4],
+                    [14, 21, 16, 12, 6],
+                ])),
+                Board::new(Grid::from([
+                    [14, 21, 17, 24, 4],
+                    [10, 16, 15, 9, 19],
+                    [18, 8, 23, 26, 20],
+                    [22, 11, 13, 6, 5],
+                    [2, 0, 12, 3, 7],
+                ])),
             ],
         );
-        assert_eq!(expected, parse_input(get_test_input()));
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
     }
+
+    #[test]
+    fn test_parse_input_error() {
+        assert_eq!(
+            Err(ParseError::InvalidNumber {
+                day: 4,
+                line: 1,
+                column: 5,
+                text: String::from("256"),
+            }),
+            parse_input("7,4,256\n")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidFormat {
+                day: 4,
+                line: 4,
+                column: 1,
+                text: String::from(" 8  2 23  4"),
+            }),
+            parse_input("7,4\n\n22 13 17 11  0\n 8  2 23  4\n")
+        );
+        assert_eq!(
+            Err(ParseError::UnexpectedEnd {
+                day: 4,
+                line: 4,
+                column: 1,
+                text: String::new(),
+            }),
+            parse_input("7,4\n\n22 13 17 11  0\n")
+        );
+    }
+
     #[test]
-    fn part1_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(4512, part1(&input));
+    fn test_visualize() {
+        let dir = std::env::temp_dir().join("advent_of_code_2021_test_day4");
+        let mut output = Output::create(&dir).unwrap();
+        let game = parse_input(get_test_input()).unwrap();
+        visualize(&game, &mut output).unwrap();
+        // The last board wins when 13 is drawn, which is the 15th number.
+        assert_eq!(16, output.files().len());
+        assert!(output.files()[15].ends_with("bingo-0015.ppm"));
+        let frame = std::fs::read(&output.files()[0]).unwrap();
+        assert!(frame.starts_with(b"P6\n104 104\n255\n"));
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 
     #[test]
-    fn part2_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(1924, part2(&input));
+    fn test_trace() {
+        let mut out = vec![];
+        let mut terminal = Terminal::new(&mut out, None);
+        trace(&parse_input(get_test_input()).unwrap(), &mut terminal).unwrap();
+        // A frame for each of the 15 draws, and one for the score.
+        assert_eq!(16, terminal.frames());
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Drew 13"));
+        assert!(out.ends_with("Score: 1924\n\n"));
     }
 
     fn get_test_input<'a>() -> &'a str {
-        indoc! {"
-            7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
-
-            22 13 17 11  0
-             8  2 23  4 24
-            21  9 14 16  7
-             6 10  3 18  5
-             1 12 20 15 19
-
-             3 15  0  2 22
-             9 18 13 17  5
-            19  8  7 25 23
-            20 11 10 24  4
-            14 21 16 12  6
-
-            14 21 17 24  4
-            10 16 15  9 19
-            18  8 23 26 20
-            22 11 13  6  5
-             2  0 12  3  7
-        "}
+        example::input(include_str!("../examples/day4/example.txt"))
     }
 }
@@ -1,5 +1,10 @@
+use crate::error::{ParseError, Span};
+use crate::grid::Grid;
+use crate::solution::{Solution, Visualize};
+use crate::viz::{self, Output, Svg};
 use aoc_runner_derive::{aoc, aoc_generator};
 use regex::Regex;
+use std::io;
 use std::ops::RangeInclusive;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -9,7 +14,7 @@ struct Coord {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
-struct Line {
+pub struct Line {
     s: Coord,
     e: Coord,
 }
@@ -35,48 +40,108 @@ impl Line {
     }
 }
 
+/// Day 5: Hydrothermal Venture
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+    type Input = Vec<Line>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
+impl Visualize for Day5 {
+    fn visualize(input: &Self::Input, output: &mut Output) -> io::Result<()> {
+        visualize(input, output)
+    }
+}
+
+/// The largest coordinate that is accepted. The grid has a cell for every point, so this keeps it
+/// below 100 MB; the puzzle inputs only use coordinates below 1000.
+const MAX_COORDINATE: usize = 9999;
+
 #[aoc_generator(day5)]
-fn parse_input(input: &str) -> Vec<Line> {
+pub fn parse_input(input: &str) -> Result<Vec<Line>, ParseError> {
     let re = Regex::new(r"^(\d+),(\d+) -> (\d+),(\d+)$").unwrap();
     input
         .lines()
-        .map(|l| {
-            let caps = re.captures(l).unwrap();
-            Line {
+        .enumerate()
+        .map(|(i, l)| {
+            let caps = re
+                .captures(l)
+                .ok_or_else(|| Span::line(5, i, l).invalid_format())?;
+            let coord = |n: usize| {
+                let span = Span::new(5, i, l, caps.get(n).unwrap().as_str());
+                let value: usize = span.parse()?;
+                if value > MAX_COORDINATE {
+                    return Err(span.out_of_range());
+                }
+                Ok(value)
+            };
+            Ok(Line {
                 s: Coord {
-                    x: caps[1].parse().unwrap(),
-                    y: caps[2].parse().unwrap(),
+                    x: coord(1)?,
+                    y: coord(2)?,
                 },
                 e: Coord {
-                    x: caps[3].parse().unwrap(),
-                    y: caps[4].parse().unwrap(),
+                    x: coord(3)?,
+                    y: coord(4)?,
                 },
-            }
+            })
         })
         .collect()
 }
 
 #[aoc(day5, part1)]
-fn part1(lines: &[Line]) -> usize {
+pub fn part1(lines: &[Line]) -> usize {
     count_danger_zones(lines, false)
 }
 
 #[aoc(day5, part2)]
-fn part2(lines: &[Line]) -> usize {
+pub fn part2(lines: &[Line]) -> usize {
     count_danger_zones(lines, true)
 }
 
 fn count_danger_zones(lines: &[Line], check_diagonal: bool) -> usize {
-    let mut grid = [[0_u8; 1000]; 1000];
+    vent_grid(lines, check_diagonal)
+        .iter()
+        .filter(|v| **v > 1)
+        .count()
+}
+
+/// Returns the number of lines that cover every point.
+fn vent_grid(lines: &[Line], check_diagonal: bool) -> Grid<u8> {
+    // Make the grid just large enough to fit all lines.
+    let width = lines
+        .iter()
+        .map(|l| l.s.x.max(l.e.x) + 1)
+        .max()
+        .unwrap_or(0);
+    let height = lines
+        .iter()
+        .map(|l| l.s.y.max(l.e.y) + 1)
+        .max()
+        .unwrap_or(0);
+    let mut grid = Grid::new(width, height, 0_u8);
 
     for line in lines {
         if line.is_orthogonal() {
             for x in line.get_x_range() {
                 for y in line.get_y_range() {
                     // Ensure that we don't overflow.
-                    if grid[x][y] < 255 {
-                        grid[x][y] += 1;
-                    }
+                    grid[(x, y)] = grid[(x, y)].saturating_add(1);
                 }
             }
         } else if check_diagonal {
@@ -84,10 +149,8 @@ fn count_danger_zones(lines: &[Line], check_diagonal: bool) -> usize {
             let mut y = line.s.y;
             loop {
                 // Ensure that we don't overflow.
-                if grid[x][y] < 255 {
-                    grid[x][y] += 1;
-                }
-                if x == line.e.x {
+                grid[(x, y)] = grid[(x, y)].saturating_add(1);
+                if x == line.e.x || y == line.e.y {
                     break;
                 }
                 if line.s.x < line.e.x {
@@ -104,22 +167,37 @@ fn count_danger_zones(lines: &[Line], check_diagonal: bool) -> usize {
         }
     }
 
-    let mut result = 0;
-    for row in &grid {
-        for value in row {
-            if *value > 1 {
-                result += 1;
-            }
-        }
-    }
+    grid
+}
 
-    result
+/// Renders the vents as a heatmap of the number of overlapping lines in `vents.ppm`, and as a
+/// drawing of the lines in `vents.svg`.
+pub fn visualize(lines: &[Line], output: &mut Output) -> io::Result<()> {
+    let grid = vent_grid(lines, true);
+    let max = grid.iter().copied().max().unwrap_or(0).max(2);
+    let heatmap = grid.map(|&v| match v {
+        0 => viz::BLACK,
+        1 => viz::GREY,
+        _ => viz::gradient(f64::from(v - 1) / f64::from(max - 1)),
+    });
+    output.write("vents.ppm", viz::ppm(&heatmap, 1))?;
+
+    let mut svg = Svg::new(grid.width(), grid.height());
+    for line in lines {
+        let color = if line.is_orthogonal() {
+            viz::YELLOW
+        } else {
+            viz::RED
+        };
+        svg.line((line.s.x, line.s.y), (line.e.x, line.e.y), color);
+    }
+    output.write("vents.svg", svg.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use indoc::indoc;
+    use crate::example;
 
     #[test]
     fn test_parse_input() {
@@ -165,32 +243,46 @@ mod tests {
                 e: Coord { x: 8, y: 2 },
             },
         ];
-        assert_eq!(expected, parse_input(get_test_input()));
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
     }
+
     #[test]
-    fn part1_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(5, part1(&input));
+    fn test_parse_input_error() {
+        assert_eq!(
+            Err(ParseError::InvalidFormat {
+                day: 5,
+                line: 2,
+                column: 1,
+                text: String::from("8,0 => 0,8"),
+            }),
+            parse_input("0,9 -> 5,9\n8,0 => 0,8\n")
+        );
     }
 
     #[test]
-    fn part2_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(12, part2(&input));
+    fn test_parse_input_out_of_range() {
+        assert_eq!(1, parse_input("0,0 -> 9999,9999\n").unwrap().len());
+        assert_eq!(
+            Err(ParseError::OutOfRange {
+                day: 5,
+                line: 2,
+                column: 8,
+                text: String::from("100000"),
+            }),
+            parse_input("0,9 -> 5,9\n0,0 -> 100000,100000\n")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidNumber {
+                day: 5,
+                line: 1,
+                column: 8,
+                text: String::from("99999999999999999999999"),
+            }),
+            parse_input("0,0 -> 99999999999999999999999,0\n")
+        );
     }
 
     fn get_test_input<'a>() -> &'a str {
-        indoc! {"
-            0,9 -> 5,9
-            8,0 -> 0,8
-            9,4 -> 3,4
-            2,2 -> 2,1
-            7,0 -> 7,4
-            6,4 -> 2,0
-            0,9 -> 2,9
-            3,4 -> 1,4
-            0,0 -> 8,8
-            5,5 -> 8,2
-        "}
+        example::input(include_str!("../examples/day5/example.txt"))
     }
 }
@@ -1,31 +1,89 @@
+use crate::error::{end_of_input, ParseError, Span};
+use crate::solution::{Solution, Streaming};
+use crate::stream::{read_list, StreamError};
 use aoc_runner_derive::{aoc, aoc_generator};
+use std::io::BufRead;
+
+/// Day 6: Lanternfish
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+    type Input = [u64; 9];
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
+impl Streaming for Day6 {
+    // The school is already summarised by the number of fish per timer.
+    type Summary = [u64; 9];
+
+    fn summarize(reader: impl BufRead) -> Result<Self::Summary, StreamError> {
+        summarize(reader)
+    }
+
+    fn summary_part1(summary: &Self::Summary) -> Self::Answer1 {
+        part1(summary)
+    }
+
+    fn summary_part2(summary: &Self::Summary) -> Self::Answer2 {
+        part2(summary)
+    }
+}
 
 #[aoc_generator(day6)]
-fn parse_input(input: &str) -> [u64; 9] {
-    let fishes: Vec<usize> = input
-        .trim()
-        .split(',')
-        .map(|v| v.parse::<usize>().unwrap())
-        .collect();
+pub fn parse_input(input: &str) -> Result<[u64; 9], ParseError> {
+    let line = input.lines().next().ok_or_else(|| end_of_input(6, input))?;
 
     let mut school = [0_u64; 9];
-    for fish in fishes {
-        school[fish] += 1;
+    for timer in line.trim_end().split(',') {
+        let span = Span::new(6, 0, line, timer);
+        let fish: usize = span.parse()?;
+        *school.get_mut(fish).ok_or_else(|| span.out_of_range())? += 1;
     }
-    school
+    Ok(school)
+}
+
+/// Counts the fish per timer while reading the input, so the timers are not kept in memory.
+pub fn summarize(reader: impl BufRead) -> Result<[u64; 9], StreamError> {
+    let mut school = [0_u64; 9];
+    for timer in read_list(6, reader, b',') {
+        let (column, fish): (usize, usize) = timer?;
+        let count = school.get_mut(fish).ok_or_else(|| ParseError::OutOfRange {
+            day: 6,
+            line: 1,
+            column,
+            text: fish.to_string(),
+        })?;
+        *count += 1;
+    }
+    Ok(school)
 }
 
 #[aoc(day6, part1)]
-fn part1(school: &[u64; 9]) -> u64 {
+pub fn part1(school: &[u64; 9]) -> u64 {
     count_school(school, 80)
 }
 
 #[aoc(day6, part2)]
-fn part2(school: &[u64; 9]) -> u64 {
+pub fn part2(school: &[u64; 9]) -> u64 {
     count_school(school, 256)
 }
 
-fn count_school(school: &[u64; 9], days: usize) -> u64 {
+/// Returns the number of lanternfish after the given number of days.
+pub(crate) fn count_school(school: &[u64; 9], days: usize) -> u64 {
     let mut school = *school;
     for _ in 0..days {
         let due = school[0];
@@ -41,28 +99,38 @@ fn count_school(school: &[u64; 9], days: usize) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use indoc::indoc;
+    use crate::example;
 
     #[test]
     fn test_parse_input() {
         let expected: [u64; 9] = [0, 1, 1, 2, 1, 0, 0, 0, 0];
-        assert_eq!(expected, parse_input(get_test_input()));
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
     }
+
     #[test]
-    fn part1_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(5934, part1(&input));
+    fn test_parse_input_error() {
+        assert_eq!(
+            Err(ParseError::OutOfRange {
+                day: 6,
+                line: 1,
+                column: 5,
+                text: String::from("9"),
+            }),
+            parse_input("3,4,9,1,2\n")
+        );
     }
 
     #[test]
-    fn part2_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(26984457539, part2(&input));
+    fn summarize_example() {
+        let expected = parse_input(get_test_input()).unwrap();
+        assert_eq!(expected, summarize(get_test_input().as_bytes()).unwrap());
+        assert!(matches!(
+            summarize("3,4,9,1\n".as_bytes()),
+            Err(StreamError::Parse(ParseError::OutOfRange { column: 5, .. }))
+        ));
     }
 
     fn get_test_input<'a>() -> &'a str {
-        indoc! {"
-            3,4,3,1,2
-        "}
+        example::input(include_str!("../examples/day6/example.txt"))
     }
 }
@@ -1,17 +1,57 @@
+use crate::error::{end_of_input, parse_list, ParseError};
+use crate::solution::{Solution, Streaming};
+use crate::stream::{read_list, StreamError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::cmp::min;
+use std::collections::BTreeMap;
+use std::io::BufRead;
+
+/// Day 7: The Treachery of Whales
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+    type Input = Vec<usize>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
+impl Streaming for Day7 {
+    type Summary = BTreeMap<usize, usize>;
+
+    fn summarize(reader: impl BufRead) -> Result<Self::Summary, StreamError> {
+        summarize(reader)
+    }
+
+    fn summary_part1(summary: &Self::Summary) -> Self::Answer1 {
+        summary_part1(summary)
+    }
+
+    fn summary_part2(summary: &Self::Summary) -> Self::Answer2 {
+        summary_part2(summary)
+    }
+}
 
 #[aoc_generator(day7)]
-fn parse_input(input: &str) -> Vec<usize> {
-    input
-        .trim()
-        .split(',')
-        .map(|v| v.parse::<usize>().unwrap())
-        .collect()
+pub fn parse_input(input: &str) -> Result<Vec<usize>, ParseError> {
+    let line = input.lines().next().ok_or_else(|| end_of_input(7, input))?;
+    parse_list(7, 0, line, ',')
 }
 
 #[aoc(day7, part1)]
-fn part1(positions: &[usize]) -> usize {
+pub fn part1(positions: &[usize]) -> usize {
     let mut positions = positions.to_owned();
     positions.sort_unstable();
     let target = positions[positions.len() / 2] as isize;
@@ -24,7 +64,7 @@ fn part1(positions: &[usize]) -> usize {
 }
 
 #[aoc(day7, part2)]
-fn part2(positions: &[usize]) -> usize {
+pub fn part2(positions: &[usize]) -> usize {
     let mut positions = positions.to_owned();
     positions.sort_unstable();
     let avg = |p: &[usize]| -> f32 { p.iter().map(|x| *x as f32).sum::<f32>() / p.len() as f32 };
@@ -42,31 +82,84 @@ fn part2(positions: &[usize]) -> usize {
     )
 }
 
+/// Counts the crabs per position while reading the input.
+pub fn summarize(reader: impl BufRead) -> Result<BTreeMap<usize, usize>, StreamError> {
+    let mut crabs = BTreeMap::new();
+    for position in read_list(7, reader, b',') {
+        let (_, position) = position?;
+        *crabs.entry(position).or_insert(0) += 1;
+    }
+    Ok(crabs)
+}
+
+/// Solves part 1 for the number of crabs per position, by aligning them on the median.
+pub fn summary_part1(crabs: &BTreeMap<usize, usize>) -> usize {
+    let count: usize = crabs.values().sum();
+    let mut seen = 0;
+    let target = crabs
+        .iter()
+        .find(|(_, crabs)| {
+            seen += *crabs;
+            seen > count / 2
+        })
+        .map(|(position, _)| *position)
+        .unwrap();
+    fuel_cost(crabs, target, |d| d)
+}
+
+/// Solves part 2 for the number of crabs per position, by aligning them on a position next to the
+/// mean.
+pub fn summary_part2(crabs: &BTreeMap<usize, usize>) -> usize {
+    let count: usize = crabs.values().sum();
+    let total: usize = crabs.iter().map(|(position, crabs)| position * crabs).sum();
+    let mean = total / count;
+    min(
+        fuel_cost(crabs, mean, |d| d * (d + 1) / 2),
+        fuel_cost(crabs, mean + 1, |d| d * (d + 1) / 2),
+    )
+}
+
+fn fuel_cost(crabs: &BTreeMap<usize, usize>, target: usize, cost: fn(usize) -> usize) -> usize {
+    crabs
+        .iter()
+        .map(|(position, crabs)| cost(position.abs_diff(target)) * crabs)
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use indoc::indoc;
+    use crate::example;
 
     #[test]
     fn test_parse_input() {
         let expected: Vec<usize> = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
-        assert_eq!(expected, parse_input(get_test_input()));
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
     }
+
     #[test]
-    fn part1_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(37, part1(&input));
+    fn test_parse_input_error() {
+        assert_eq!(
+            Err(ParseError::InvalidNumber {
+                day: 7,
+                line: 1,
+                column: 6,
+                text: String::from("-2"),
+            }),
+            parse_input("16,1,-2,0\n")
+        );
+        assert!(parse_input("").is_err());
     }
 
     #[test]
-    fn part2_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(168, part2(&input));
+    fn summarize_example() {
+        let crabs = summarize(get_test_input().as_bytes()).unwrap();
+        assert_eq!(Some(&3), crabs.get(&2));
+        assert_eq!(37, summary_part1(&crabs));
+        assert_eq!(168, summary_part2(&crabs));
     }
 
     fn get_test_input<'a>() -> &'a str {
-        indoc! {"
-            16,1,2,0,4,2,7,1,2,14
-        "}
+        example::input(include_str!("../examples/day7/example.txt"))
     }
 }
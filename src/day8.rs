@@ -1,30 +1,80 @@
+use crate::error::{ParseError, Span};
+use crate::solution::Solution;
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
-use std::string::ToString;
 use std::vec;
 
+/// A note contains the ten unique signal patterns, and the four digits of the output value.
+pub type Note = (Vec<String>, Vec<String>);
+
+/// Day 8: Seven Segment Search
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+    type Input = Vec<Note>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[aoc_generator(day8)]
-fn parse_input(input: &str) -> Vec<(Vec<String>, Vec<String>)> {
+pub fn parse_input(input: &str) -> Result<Vec<Note>, ParseError> {
     input
         .lines()
-        .map(|l| {
-            let parts = l.split(" | ").collect::<Vec<&str>>();
-            (
-                parts[0]
-                    .split_whitespace()
-                    .map(ToString::to_string)
-                    .collect(),
-                parts[1]
-                    .split_whitespace()
-                    .map(ToString::to_string)
-                    .collect(),
-            )
+        .enumerate()
+        .map(|(i, l)| {
+            let (patterns, digits) = l
+                .split_once(" | ")
+                .ok_or_else(|| Span::line(8, i, l).invalid_format())?;
+            Ok((
+                parse_patterns(i, l, patterns, 10)?,
+                parse_patterns(i, l, digits, 4)?,
+            ))
         })
         .collect()
 }
 
+/// Parses a list of the given number of segment patterns, taken from the given line.
+fn parse_patterns(
+    index: usize,
+    line: &str,
+    list: &str,
+    count: usize,
+) -> Result<Vec<String>, ParseError> {
+    let patterns = list
+        .split_whitespace()
+        .map(|p| {
+            let span = Span::new(8, index, line, p);
+            if let Some((j, c)) = p.char_indices().find(|(_, c)| !('a'..='g').contains(c)) {
+                return Err(span.char_at(j, c).invalid_character());
+            }
+            // A digit lights up between 2 and 7 segments.
+            if !(2..=7).contains(&p.len()) {
+                return Err(span.invalid_format());
+            }
+            Ok(p.to_string())
+        })
+        .collect::<Result<Vec<String>, ParseError>>()?;
+    if patterns.len() != count {
+        return Err(Span::new(8, index, line, list).invalid_format());
+    }
+    Ok(patterns)
+}
+
 #[aoc(day8, part1)]
-fn part1(notes: &[(Vec<String>, Vec<String>)]) -> usize {
+pub fn part1(notes: &[Note]) -> usize {
     const FIXED_WIDTH_DIGIT_LENGTHS: [usize; 4] = [2, 3, 4, 7];
     notes
         .iter()
@@ -38,7 +88,7 @@ fn part1(notes: &[(Vec<String>, Vec<String>)]) -> usize {
 }
 
 #[aoc(day8, part2)]
-fn part2(notes: &[(Vec<String>, Vec<String>)]) -> usize {
+pub fn part2(notes: &[Note]) -> usize {
     let superset = |set: &Vec<String>, search: &String| -> String {
         set.iter()
             .fold(None, |acc, p| {
@@ -161,7 +211,7 @@ fn part2(notes: &[(Vec<String>, Vec<String>)]) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use indoc::indoc;
+    use crate::example;
 
     #[test]
     fn test_parse_input() {
@@ -367,32 +417,34 @@ mod tests {
                 ],
             ),
         ];
-        assert_eq!(expected, parse_input(get_test_input()));
-    }
-    #[test]
-    fn part1_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(26, part1(&input),);
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
     }
 
     #[test]
-    fn part2_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(61229, part2(&input),);
+    fn test_parse_input_error() {
+        assert_eq!(
+            Err(ParseError::InvalidFormat {
+                day: 8,
+                line: 1,
+                column: 1,
+                text: String::from("be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb"),
+            }),
+            parse_input("be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb\n")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidCharacter {
+                day: 8,
+                line: 1,
+                column: 67,
+                text: String::from("h"),
+            }),
+            parse_input(
+                "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgach cefdb cefbgd gcbe\n"
+            )
+        );
     }
 
     fn get_test_input<'a>() -> &'a str {
-        indoc! {"
-            be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
-            edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
-            fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg
-            fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb
-            aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf egdcabf bgf bfgea
-            fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fadegcb
-            dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe
-            bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef
-            egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
-            gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
-        "}
+        example::input(include_str!("../examples/day8/example.txt"))
     }
 }
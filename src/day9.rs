@@ -1,147 +1,230 @@
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::solution::{Solution, Trace, Visualize};
+use crate::trace::{self, Terminal};
+use crate::viz::{self, Output};
 use aoc_runner_derive::{aoc, aoc_generator};
+use std::io;
+
+/// Day 9: Smoke Basin
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+    type Input = Grid<u8>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
+impl Visualize for Day9 {
+    fn visualize(input: &Self::Input, output: &mut Output) -> io::Result<()> {
+        visualize(input, output)
+    }
+}
+
+impl Trace for Day9 {
+    fn trace(input: &Self::Input, terminal: &mut Terminal) -> io::Result<()> {
+        trace(input, terminal)
+    }
+}
 
 #[aoc_generator(day9)]
-fn parse_input(input: &str) -> Vec<Vec<u8>> {
-    input
-        .lines()
-        .map(|l| {
-            l.chars()
-                .map(|c| c.to_string().parse::<u8>().unwrap())
-                .collect()
-        })
-        .collect()
+pub fn parse_input(input: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::parse(9, input, |c| c.to_digit(10).map(|d| d as u8))
 }
 
 #[aoc(day9, part1)]
-fn part1(heightmap: &[Vec<u8>]) -> usize {
-    let mut risk_levels: Vec<usize> = vec![];
-    let xsize = heightmap.len();
-    let ysize = heightmap[0].len();
-    for x in 0..xsize {
-        for y in 0..ysize {
-            let curval = &heightmap[x][y];
-            // Up.
-            if y > 0 && heightmap[x][y - 1] <= *curval {
-                continue;
-            }
-            // Down.
-            if y < ysize - 1 && heightmap[x][y + 1] <= *curval {
-                continue;
-            }
-            // Left.
-            if x > 0 && heightmap[x - 1][y] <= *curval {
-                continue;
-            }
-            // Right.
-            if x < xsize - 1 && heightmap[x + 1][y] <= *curval {
-                continue;
-            }
-            risk_levels.push(*curval as usize + 1);
-        }
-    }
-    risk_levels.into_iter().sum()
+pub fn part1(heightmap: &Grid<u8>) -> usize {
+    low_points(heightmap)
+        .map(|p| heightmap[p] as usize + 1)
+        .sum()
 }
 
+/// Returns the product of the sizes of the three largest basins. A map with fewer basins gives
+/// the product of the basins it has, so a map without basins gives 1.
 #[aoc(day9, part2)]
-fn part2(heightmap: &[Vec<u8>]) -> usize {
-    let mut heightmap = heightmap.to_owned();
-    let xsize = heightmap.len();
-    let ysize = heightmap[0].len();
-
-    let mut size = 0_usize;
-    let mut sizes = vec![];
-    let mut queue: Vec<(usize, usize)> = vec![];
-
-    // Seed the queue with a starting coordinate.
-    while {
-        let startcoord = get_basin_coord(&heightmap);
-        if let Some(coord) = startcoord {
-            queue.push(coord);
-            size = 0;
-        }
-        startcoord.is_some()
-    } {
-        while !queue.is_empty() {
-            let (x, y) = queue.pop().unwrap();
+pub fn part2(heightmap: &Grid<u8>) -> usize {
+    let mut sizes: Vec<usize> = basins(heightmap, |_, _| {}).iter().map(Vec::len).collect();
+    sizes.sort_unstable();
+    sizes.iter().rev().take(3).product()
+}
 
+fn low_points(heightmap: &Grid<u8>) -> impl Iterator<Item = (usize, usize)> + '_ {
+    heightmap.positions().filter(|&(x, y)| {
+        let height = heightmap[(x, y)];
+        heightmap.neighbours(x, y).all(|n| heightmap[n] > height)
+    })
+}
+
+/// Returns the positions in every basin. The hook is called with the index of the basin and the
+/// positions filled in so far, after every position of a basin is filled in.
+fn basins(
+    heightmap: &Grid<u8>,
+    mut hook: impl FnMut(usize, &[(usize, usize)]),
+) -> Vec<Vec<(usize, usize)>> {
+    let mut heightmap = heightmap.clone();
+    let mut basins = vec![];
+
+    // Every position that is not filled in yet is the starting point of a new basin.
+    while let Some(start) = heightmap.position(|h| *h < 9) {
+        let mut basin = vec![];
+        let mut queue = vec![start];
+
+        while let Some((x, y)) = queue.pop() {
             // Fill in the position so it will not be counted again.
-            if heightmap[x][y] < 9 {
-                heightmap[x][y] = 9;
-                size += 1;
+            if heightmap[(x, y)] < 9 {
+                heightmap[(x, y)] = 9;
+                basin.push((x, y));
+                hook(basins.len(), &basin);
             }
+            queue.extend(heightmap.neighbours(x, y).filter(|&n| heightmap[n] < 9));
+        }
+        basins.push(basin);
+    }
+    basins
+}
 
-            // Up.
-            if y > 0 && heightmap[x][y - 1] < 9 {
-                queue.push((x, y - 1));
-            }
-            // Down.
-            if y < ysize - 1 && heightmap[x][y + 1] < 9 {
-                queue.push((x, y + 1));
-            }
-            // Left.
-            if x > 0 && heightmap[x - 1][y] < 9 {
-                queue.push((x - 1, y));
-            }
-            // Right.
-            if x < xsize - 1 && heightmap[x + 1][y] < 9 {
-                queue.push((x + 1, y));
-            }
+/// Renders the basins in `basins.ppm`. Every basin has its own colour, which is darker for higher
+/// locations. The three largest basins are brighter than the others, and the low points are white.
+pub fn visualize(heightmap: &Grid<u8>, output: &mut Output) -> io::Result<()> {
+    let mut basins = basins(heightmap, |_, _| {});
+    basins.sort_by_key(|basin| std::cmp::Reverse(basin.len()));
+
+    let mut image = Grid::new(heightmap.width(), heightmap.height(), viz::BLACK);
+    for (i, basin) in basins.iter().enumerate() {
+        let brightness = if i < 3 { 1.0 } else { 0.6 };
+        for &p in basin {
+            let height = f64::from(heightmap[p]) / 9.0;
+            image[p] = viz::shade(viz::palette(i), brightness * (1.0 - height * 0.7));
         }
-        sizes.push(size);
     }
+    for p in low_points(heightmap) {
+        image[p] = viz::WHITE;
+    }
+    output.write("basins.ppm", viz::ppm(&image, 4))
+}
 
-    sizes.sort_unstable();
-    sizes.as_slice()[sizes.len() - 3..].iter().product()
+/// Prints the heightmap after every location that is filled in, so the basins can be seen growing.
+/// The basin that is being filled is highlighted, and the earlier basins are coloured.
+pub fn trace(heightmap: &Grid<u8>, terminal: &mut Terminal) -> io::Result<()> {
+    let mut owners: Grid<Option<usize>> = heightmap.map(|_| None);
+    let mut result = Ok(());
+    basins(heightmap, |index, basin| {
+        let last = *basin
+            .last()
+            .expect("the hook is called after filling in a location");
+        owners[last] = Some(index);
+        if result.is_ok() {
+            let title = format!("Filling basin {}, size {}", index + 1, basin.len());
+            result = terminal.frame(&title, &render_text(heightmap, &owners, index));
+        }
+    });
+    result
 }
 
-fn get_basin_coord(heightmap: &[Vec<u8>]) -> Option<(usize, usize)> {
-    let xsize = heightmap.len();
-    let ysize = heightmap[0].len();
-    for x in 0..xsize {
-        for y in 0..ysize {
-            if heightmap[x][y] < 9 {
-                return Some((x, y));
-            }
+/// Renders the heightmap, colouring every location by the basin it belongs to.
+fn render_text(heightmap: &Grid<u8>, owners: &Grid<Option<usize>>, current: usize) -> String {
+    let mut text = String::new();
+    for y in 0..heightmap.height() {
+        for x in 0..heightmap.width() {
+            let height = heightmap[(x, y)];
+            let cell = match owners[(x, y)] {
+                Some(basin) if basin == current => trace::bg(height, viz::palette(basin)),
+                Some(basin) => trace::fg(height, viz::palette(basin)),
+                None if height == 9 => trace::dim(height),
+                None => height.to_string(),
+            };
+            text.push_str(&cell);
         }
+        text.push('\n');
     }
-    None
+    text
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use indoc::indoc;
+    use crate::example;
 
     #[test]
     fn test_parse_input() {
-        let expected: Vec<Vec<u8>> = vec![
-            vec![2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
-            vec![3, 9, 8, 7, 8, 9, 4, 9, 2, 1],
-            vec![9, 8, 5, 6, 7, 8, 9, 8, 9, 2],
-            vec![8, 7, 6, 7, 8, 9, 6, 7, 8, 9],
-            vec![9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
-        ];
-        assert_eq!(expected, parse_input(get_test_input()));
+        let expected = Grid::from([
+            [2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
+            [3, 9, 8, 7, 8, 9, 4, 9, 2, 1],
+            [9, 8, 5, 6, 7, 8, 9, 8, 9, 2],
+            [8, 7, 6, 7, 8, 9, 6, 7, 8, 9],
+            [9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
+        ]);
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
+    }
+
+    #[test]
+    fn test_parse_input_error() {
+        assert_eq!(
+            Err(ParseError::InvalidCharacter {
+                day: 9,
+                line: 2,
+                column: 4,
+                text: String::from("x"),
+            }),
+            parse_input("2199943210\n398x894921\n")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidFormat {
+                day: 9,
+                line: 2,
+                column: 1,
+                text: String::from("398789492"),
+            }),
+            parse_input("2199943210\n398789492\n")
+        );
+    }
+
+    #[test]
+    fn test_basins() {
+        let input = parse_input(get_test_input()).unwrap();
+        let mut sizes: Vec<usize> = basins(&input, |_, _| {}).iter().map(Vec::len).collect();
+        sizes.sort_unstable();
+        assert_eq!(vec![3, 9, 9, 14], sizes);
+        assert_eq!(
+            vec![(1, 0), (9, 0), (2, 2), (6, 4)],
+            low_points(&input).collect::<Vec<_>>()
+        );
     }
+
     #[test]
-    fn part1_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(15, part1(&input));
+    fn test_part2_few_basins() {
+        assert_eq!(6, part2(&parse_input("119\n999\n111\n").unwrap()));
+        assert_eq!(1, part2(&parse_input("999\n999\n").unwrap()));
     }
 
     #[test]
-    fn part2_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(1134, part2(&input));
+    fn test_trace() {
+        let mut out = vec![];
+        let mut terminal = Terminal::new(&mut out, None);
+        trace(&parse_input(get_test_input()).unwrap(), &mut terminal).unwrap();
+        // A frame for every location in the four basins.
+        assert_eq!(3 + 9 + 14 + 9, terminal.frames());
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Filling basin 1, size 1"));
+        assert!(out.contains("Filling basin 1, size 3"));
+        assert!(!out.contains("Filling basin 1, size 4"));
+        assert!(out.contains("Filling basin 4, size 9"));
     }
 
     fn get_test_input<'a>() -> &'a str {
-        indoc! {"
-            2199943210
-            3987894921
-            9856789892
-            8767896789
-            9899965678
-        "}
+        example::input(include_str!("../examples/day9/example.txt"))
     }
 }
@@ -0,0 +1,279 @@
+use std::error::Error;
+use std::fmt;
+
+/// An error that occurred while parsing a puzzle input.
+///
+/// Line and column numbers are 1-based, so they can be used to look up the offending text in an
+/// editor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// A value could not be parsed as a number.
+    InvalidNumber {
+        day: u8,
+        line: usize,
+        column: usize,
+        text: String,
+    },
+    /// A number was parsed but is outside of the range allowed by the puzzle.
+    OutOfRange {
+        day: u8,
+        line: usize,
+        column: usize,
+        text: String,
+    },
+    /// A character that is not allowed by the puzzle.
+    InvalidCharacter {
+        day: u8,
+        line: usize,
+        column: usize,
+        text: String,
+    },
+    /// A line or a part of a line that does not have the expected structure.
+    InvalidFormat {
+        day: u8,
+        line: usize,
+        column: usize,
+        text: String,
+    },
+    /// The input ended while more data was expected.
+    UnexpectedEnd {
+        day: u8,
+        line: usize,
+        column: usize,
+        text: String,
+    },
+}
+
+impl ParseError {
+    /// Returns the day of the puzzle whose input failed to parse.
+    pub const fn day(&self) -> u8 {
+        match self {
+            Self::InvalidNumber { day, .. }
+            | Self::OutOfRange { day, .. }
+            | Self::InvalidCharacter { day, .. }
+            | Self::InvalidFormat { day, .. }
+            | Self::UnexpectedEnd { day, .. } => *day,
+        }
+    }
+
+    /// Returns the 1-based line number on which the error occurred.
+    pub const fn line(&self) -> usize {
+        match self {
+            Self::InvalidNumber { line, .. }
+            | Self::OutOfRange { line, .. }
+            | Self::InvalidCharacter { line, .. }
+            | Self::InvalidFormat { line, .. }
+            | Self::UnexpectedEnd { line, .. } => *line,
+        }
+    }
+
+    /// Returns the 1-based column on which the offending text starts.
+    pub const fn column(&self) -> usize {
+        match self {
+            Self::InvalidNumber { column, .. }
+            | Self::OutOfRange { column, .. }
+            | Self::InvalidCharacter { column, .. }
+            | Self::InvalidFormat { column, .. }
+            | Self::UnexpectedEnd { column, .. } => *column,
+        }
+    }
+
+    /// Returns the offending text.
+    pub fn text(&self) -> &str {
+        match self {
+            Self::InvalidNumber { text, .. }
+            | Self::OutOfRange { text, .. }
+            | Self::InvalidCharacter { text, .. }
+            | Self::InvalidFormat { text, .. }
+            | Self::UnexpectedEnd { text, .. } => text,
+        }
+    }
+
+    const fn description(&self) -> &'static str {
+        match self {
+            Self::InvalidNumber { .. } => "invalid number",
+            Self::OutOfRange { .. } => "number out of range",
+            Self::InvalidCharacter { .. } => "invalid character",
+            Self::InvalidFormat { .. } => "invalid format",
+            Self::UnexpectedEnd { .. } => "unexpected end of input",
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: {} {:?}",
+            self.day(),
+            self.line(),
+            self.column(),
+            self.description(),
+            self.text()
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// Points to a piece of text on a line of a puzzle input.
+///
+/// The `text` is expected to be a slice of `line`, so its column can be derived from its position
+/// in memory. This allows the generators to report errors on text that was obtained by splitting
+/// or matching a line without keeping track of offsets.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Span<'a> {
+    pub day: u8,
+    /// The 0-based index of the line, as returned by `enumerate()`.
+    pub index: usize,
+    pub line: &'a str,
+    pub text: &'a str,
+}
+
+// Every helper is used by some day, but not necessarily by the days that are enabled.
+#[cfg_attr(not(feature = "all"), allow(dead_code))]
+impl<'a> Span<'a> {
+    pub const fn new(day: u8, index: usize, line: &'a str, text: &'a str) -> Self {
+        Self {
+            day,
+            index,
+            line,
+            text,
+        }
+    }
+
+    /// Returns a span covering a complete line.
+    pub const fn line(day: u8, index: usize, line: &'a str) -> Self {
+        Self::new(day, index, line, line)
+    }
+
+    fn column(&self) -> usize {
+        let start = self.line.as_ptr() as usize;
+        let offset = (self.text.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|o| o + self.text.len() <= self.line.len())
+            .unwrap_or(0);
+        self.line
+            .get(..offset)
+            .map_or(0, |prefix| prefix.chars().count())
+            + 1
+    }
+
+    pub fn invalid_number(&self) -> ParseError {
+        ParseError::InvalidNumber {
+            day: self.day,
+            line: self.index + 1,
+            column: self.column(),
+            text: self.text.to_string(),
+        }
+    }
+
+    pub fn out_of_range(&self) -> ParseError {
+        ParseError::OutOfRange {
+            day: self.day,
+            line: self.index + 1,
+            column: self.column(),
+            text: self.text.to_string(),
+        }
+    }
+
+    pub fn invalid_character(&self) -> ParseError {
+        ParseError::InvalidCharacter {
+            day: self.day,
+            line: self.index + 1,
+            column: self.column(),
+            text: self.text.to_string(),
+        }
+    }
+
+    pub fn invalid_format(&self) -> ParseError {
+        ParseError::InvalidFormat {
+            day: self.day,
+            line: self.index + 1,
+            column: self.column(),
+            text: self.text.to_string(),
+        }
+    }
+
+    pub fn unexpected_end(&self) -> ParseError {
+        ParseError::UnexpectedEnd {
+            day: self.day,
+            line: self.index + 1,
+            column: self.column(),
+            text: self.text.to_string(),
+        }
+    }
+
+    /// Parses the text as a number.
+    pub fn parse<T: std::str::FromStr>(&self) -> Result<T, ParseError> {
+        self.text.parse().map_err(|_| self.invalid_number())
+    }
+
+    /// Returns a span for the given character of the text.
+    pub fn char_at(&self, index: usize, c: char) -> Span<'a> {
+        let text = &self.text[index..index + c.len_utf8()];
+        Span::new(self.day, self.index, self.line, text)
+    }
+}
+
+/// Parses a line containing a list of numbers separated by `separator`.
+#[cfg_attr(not(feature = "all"), allow(dead_code))]
+pub(crate) fn parse_list<T: std::str::FromStr>(
+    day: u8,
+    index: usize,
+    line: &str,
+    separator: char,
+) -> Result<Vec<T>, ParseError> {
+    line.trim_end()
+        .split(separator)
+        .map(|v| Span::new(day, index, line, v).parse())
+        .collect()
+}
+
+/// Returns the error for an input that ended before all the expected data was read.
+pub(crate) fn end_of_input(day: u8, input: &str) -> ParseError {
+    ParseError::UnexpectedEnd {
+        day,
+        line: input.lines().count() + 1,
+        column: 1,
+        text: String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_column() {
+        let line = "forward x5";
+        let span = Span::new(2, 4, line, &line[8..]);
+        assert_eq!(
+            ParseError::InvalidNumber {
+                day: 2,
+                line: 5,
+                column: 9,
+                text: String::from("x5"),
+            },
+            span.invalid_number()
+        );
+    }
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(Ok(vec![3, 4, 3]), parse_list::<u8>(6, 0, "3,4,3\n", ','));
+        let error = parse_list::<u8>(6, 0, "3,4,,3", ',').unwrap_err();
+        assert_eq!(5, error.column());
+        assert_eq!("", error.text());
+    }
+
+    #[test]
+    fn test_display() {
+        let line = "16,1,a2";
+        let error = Span::new(7, 0, line, &line[5..]).invalid_number();
+        assert_eq!(
+            "day 7, line 1, column 6: invalid number \"a2\"",
+            error.to_string()
+        );
+    }
+}
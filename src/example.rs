@@ -0,0 +1,235 @@
+//! The example inputs of the puzzles, with their expected answers.
+//!
+//! Examples are stored as text files in `examples/dayN/`, so a new example can be added without
+//! writing any code. A file starts with a header that holds the expected answers, one per line as
+//! `part<N>: <answer>`, followed by a line containing only `---` and the input itself:
+//!
+//! ```text
+//! # The example from the puzzle description.
+//! part1: 150
+//! part2: 900
+//! ---
+//! forward 5
+//! down 5
+//! ```
+//!
+//! Empty lines and lines starting with `#` in the header are ignored. A part without an expected
+//! answer is not checked.
+
+use crate::answer::Answer;
+use crate::runner::{Part, RunError};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The directory containing the examples, relative to the root of the crate.
+pub const EXAMPLE_DIR: &str = "examples";
+
+/// The line that separates the header of an example from its input.
+const SEPARATOR: &str = "---";
+
+/// An example input of a puzzle, with its expected answers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Example {
+    pub day: u8,
+    /// The name of the file, without the extension.
+    pub name: String,
+    pub input: String,
+    answers: [Option<String>; 2],
+}
+
+impl Example {
+    /// Parses the contents of an example file.
+    ///
+    /// On failure the 1-based number of the offending line is returned.
+    pub fn parse(day: u8, name: &str, text: &str) -> Result<Self, usize> {
+        let mut answers = [None, None];
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line == SEPARATOR {
+                return Ok(Self {
+                    day,
+                    name: name.to_string(),
+                    input: input(text).to_string(),
+                    answers,
+                });
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let part = line
+                .split_once(':')
+                .and_then(|(key, answer)| Some((key.strip_prefix("part")?, answer.trim())))
+                .filter(|(_, answer)| !answer.is_empty())
+                .and_then(|(number, answer)| {
+                    Some((Part::from_number(number.parse().ok()?)?, answer))
+                });
+            match part {
+                Some((part, answer)) => {
+                    answers[part.number() as usize - 1] = Some(answer.to_string())
+                }
+                None => return Err(i + 1),
+            }
+        }
+        // The separator is missing.
+        Err(text.lines().count() + 1)
+    }
+
+    /// Loads the example file at the given path, for the given day.
+    pub fn load(day: u8, path: &Path) -> Result<Self, RunError> {
+        let text = fs::read_to_string(path).map_err(|source| RunError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        Self::parse(day, &name, &text).map_err(|line| RunError::Answers {
+            path: path.to_path_buf(),
+            line,
+        })
+    }
+
+    /// Returns the expected answer of a part, as it would be recorded in an answers file.
+    pub fn answer(&self, part: Part) -> Option<&str> {
+        self.answers[part.number() as usize - 1].as_deref()
+    }
+
+    /// Compares an answer with the expected answer of a part. An answer that is not expected
+    /// always matches.
+    pub fn matches(&self, part: Part, answer: &Answer) -> bool {
+        self.answer(part)
+            .is_none_or(|expected| answer.matches(expected))
+    }
+}
+
+/// Returns the input of an example file, without its header. A file without a header is returned
+/// as a whole.
+///
+/// This allows tests to embed an example with `include_str!()`.
+pub fn input(text: &str) -> &str {
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        offset += line.len();
+        if line.trim() == SEPARATOR {
+            return &text[offset..];
+        }
+    }
+    text
+}
+
+/// Loads all examples in the given directory, ordered by day and name.
+///
+/// The examples of a day are the `.txt` files in the `dayN` subdirectory. Other files and
+/// directories are ignored.
+pub fn discover(dir: &Path) -> Result<Vec<Example>, RunError> {
+    let io_error = |path: &Path| {
+        let path = path.to_path_buf();
+        move |source| RunError::Io { path, source }
+    };
+    let mut paths: Vec<(u8, PathBuf)> = vec![];
+    for entry in fs::read_dir(dir).map_err(io_error(dir))? {
+        let day_dir = entry.map_err(io_error(dir))?.path();
+        let day = day_dir
+            .file_name()
+            .and_then(|name| name.to_str()?.strip_prefix("day")?.parse().ok());
+        let (Some(day), true) = (day, day_dir.is_dir()) else {
+            continue;
+        };
+        for entry in fs::read_dir(&day_dir).map_err(io_error(&day_dir))? {
+            let path = entry.map_err(io_error(&day_dir))?.path();
+            if path.extension().is_some_and(|e| e == "txt") {
+                paths.push((day, path));
+            }
+        }
+    }
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|(day, path)| Example::load(day, &path))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse() {
+        let example = Example::parse(2, "example", get_test_input()).unwrap();
+        assert_eq!(2, example.day);
+        assert_eq!("example", example.name);
+        assert_eq!("forward 5\ndown 5\n", example.input);
+        assert_eq!(Some("150"), example.answer(Part::One));
+        assert_eq!(None, example.answer(Part::Two));
+        assert!(example.matches(Part::One, &Answer::Integer(150)));
+        assert!(!example.matches(Part::One, &Answer::Integer(900)));
+        assert!(example.matches(Part::Two, &Answer::Integer(900)));
+
+        assert_eq!(Err(1), Example::parse(2, "example", "part3: 1\n---\n"));
+        assert_eq!(Err(1), Example::parse(2, "example", "part1:\n---\n"));
+        assert_eq!(Err(3), Example::parse(2, "example", "part1: 1\npart2: 2\n"));
+    }
+
+    #[test]
+    fn test_input() {
+        assert_eq!("forward 5\ndown 5\n", input(get_test_input()));
+        assert_eq!("forward 5\n", input("forward 5\n"));
+    }
+
+    /// Solves all examples in `examples/` and compares the answers with the expected answers.
+    /// The days that can stream their input are also checked while streaming. Examples of days
+    /// that are disabled by their cargo feature are skipped.
+    #[test]
+    fn test_examples() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(EXAMPLE_DIR);
+        let examples = discover(&dir).unwrap();
+        assert!(!examples.is_empty());
+
+        let mut failures = vec![];
+        for example in &examples {
+            let Some(puzzle) = runner::puzzle(example.day) else {
+                continue;
+            };
+            let input = puzzle
+                .parse(&example.input)
+                .unwrap_or_else(|e| panic!("day {}, {}: {}", example.day, example.name, e));
+            let summary = puzzle
+                .summarize_reader(&mut example.input.as_bytes())
+                .map(|summary| {
+                    summary
+                        .unwrap_or_else(|e| panic!("day {}, {}: {}", example.day, example.name, e))
+                });
+            for part in Part::ALL {
+                let mut answers = vec![("parsed", puzzle.solve(part, &input).unwrap())];
+                if let Some(summary) = &summary {
+                    answers.push(("streamed", puzzle.solve_summary(part, summary).unwrap()));
+                }
+                for (mode, answer) in answers {
+                    if !example.matches(part, &answer) {
+                        failures.push(format!(
+                            "day {}, {}, part {} ({}): expected {} but got {}",
+                            example.day,
+                            example.name,
+                            part,
+                            mode,
+                            example.answer(part).unwrap(),
+                            answer
+                        ));
+                    }
+                }
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+            # The example from the puzzle description.
+            part1: 150
+
+            ---
+            forward 5
+            down 5
+        "}
+    }
+}
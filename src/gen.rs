@@ -0,0 +1,392 @@
+//! Random puzzle inputs for property and stress testing.
+//!
+//! Every generator produces an input that is valid for the puzzle of the day, so it can be parsed
+//! and solved without errors. The size is a day specific measure of the amount of input, e.g. the
+//! number of lines, and inputs are fully determined by the seed of the random number generator.
+
+use rand::rngs::StdRng;
+use rand::seq::{index, SliceRandom};
+use rand::{Rng, SeedableRng};
+
+/// The segments that are lit for each digit, when the display is wired correctly.
+pub const DIGIT_SEGMENTS: [&str; 10] = [
+    "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+];
+
+/// The opening and closing characters of the chunks of day 10.
+const CHUNK_PAIRS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+
+/// The maximum number of open chunks on a line of day 10, so the completion scores fit in a
+/// `u64`.
+const MAX_CHUNK_DEPTH: usize = 20;
+
+/// Returns a random input for the given day, or `None` if the day has not been solved.
+///
+/// The size is at least 1.
+pub fn input(day: u8, size: usize, seed: u64) -> Option<String> {
+    let rng = &mut StdRng::seed_from_u64(seed);
+    let size = size.max(1);
+    let input = match day {
+        1 => day1(rng, size),
+        2 => day2(rng, size),
+        3 => day3(rng, size),
+        4 => day4(rng, size),
+        5 => day5(rng, size),
+        6 => day6(rng, size),
+        7 => day7(rng, size),
+        8 => day8(rng, size),
+        9 => day9(rng, size),
+        10 => day10(rng, size),
+        _ => return None,
+    };
+    Some(input)
+}
+
+/// Returns a sonar sweep of `size` depth measurements.
+pub fn day1(rng: &mut impl Rng, size: usize) -> String {
+    let mut depth: i32 = rng.random_range(100..200);
+    let mut input = String::new();
+    for _ in 0..size {
+        input.push_str(&format!("{}\n", depth));
+        depth = (depth + rng.random_range(-20..=30)).max(0);
+    }
+    input
+}
+
+/// Returns `size` submarine commands. The aim never becomes negative.
+pub fn day2(rng: &mut impl Rng, size: usize) -> String {
+    let mut aim = 0;
+    let mut input = String::new();
+    for _ in 0..size {
+        let amount = rng.random_range(1..=9);
+        // Always steer upwards when the aim gets large, so the depth does not overflow.
+        let command = match rng.random_range(0..3) {
+            _ if aim > 100 => "up",
+            0 => "forward",
+            1 => "down",
+            _ if amount <= aim => "up",
+            _ => "forward",
+        };
+        match command {
+            "down" => aim += amount,
+            "up" => aim -= amount,
+            _ => {}
+        }
+        input.push_str(&format!("{} {}\n", command, amount));
+    }
+    input
+}
+
+/// Returns a diagnostic report of `size` distinct binary numbers of at least 12 bits.
+///
+/// The numbers are chosen so that the oxygen generator and CO2 scrubber ratings exist.
+pub fn day3(rng: &mut impl Rng, size: usize) -> String {
+    let mut bits = 12;
+    while size > 1 << (bits - 1) {
+        bits += 1;
+    }
+    let numbers = loop {
+        let numbers = index::sample(rng, 1 << bits, size).into_vec();
+        if has_rating(&numbers, bits, true) && has_rating(&numbers, bits, false) {
+            break numbers;
+        }
+    };
+    numbers
+        .iter()
+        .map(|n| format!("{:0width$b}\n", n, width = bits))
+        .collect()
+}
+
+/// Checks that filtering the numbers on the most or least common bits leaves a number.
+fn has_rating(numbers: &[usize], bits: usize, most_common: bool) -> bool {
+    let mut numbers = numbers.to_vec();
+    for i in (0..bits).rev() {
+        if numbers.len() == 1 {
+            break;
+        }
+        let ones = numbers.iter().filter(|n| *n >> i & 1 == 1).count();
+        let zeros = numbers.len() - ones;
+        let keep = if most_common {
+            ones >= zeros
+        } else {
+            ones < zeros
+        };
+        numbers.retain(|n| (*n >> i & 1 == 1) == keep);
+        if numbers.is_empty() {
+            return false;
+        }
+    }
+    true
+}
+
+/// Returns a bingo game with `size` boards. All numbers from 0 to 99 are drawn, so every board
+/// wins eventually.
+pub fn day4(rng: &mut impl Rng, size: usize) -> String {
+    let mut draws: Vec<usize> = (0..100).collect();
+    draws.shuffle(rng);
+    let mut input = draws
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join(",");
+    input.push('\n');
+
+    for _ in 0..size {
+        input.push('\n');
+        let numbers = index::sample(rng, 100, 25).into_vec();
+        for row in numbers.chunks(5) {
+            let row: Vec<String> = row.iter().map(|n| format!("{:>2}", n)).collect();
+            input.push_str(&row.join(" "));
+            input.push('\n');
+        }
+    }
+    input
+}
+
+/// Returns `size` lines of hydrothermal vents, which are horizontal, vertical or diagonal at 45
+/// degrees. All coordinates are below 1000.
+pub fn day5(rng: &mut impl Rng, size: usize) -> String {
+    let mut input = String::new();
+    for _ in 0..size {
+        let (x1, y1): (i32, i32) = (rng.random_range(0..1000), rng.random_range(0..1000));
+        let (x2, y2) = match rng.random_range(0..3) {
+            0 => (rng.random_range(0..1000), y1),
+            1 => (x1, rng.random_range(0..1000)),
+            _ => {
+                let dx = if rng.random_bool(0.5) { 1 } else { -1 };
+                let dy = if rng.random_bool(0.5) { 1 } else { -1 };
+                let max_x = if dx > 0 { 999 - x1 } else { x1 };
+                let max_y = if dy > 0 { 999 - y1 } else { y1 };
+                let length = rng.random_range(0..=max_x.min(max_y));
+                (x1 + dx * length, y1 + dy * length)
+            }
+        };
+        input.push_str(&format!("{},{} -> {},{}\n", x1, y1, x2, y2));
+    }
+    input
+}
+
+/// Returns the internal timers of `size` lanternfish.
+pub fn day6(rng: &mut impl Rng, size: usize) -> String {
+    let timers: Vec<String> = (0..size)
+        .map(|_| rng.random_range(1..=5).to_string())
+        .collect();
+    timers.join(",") + "\n"
+}
+
+/// Returns the horizontal positions of `size` crabs.
+pub fn day7(rng: &mut impl Rng, size: usize) -> String {
+    let positions: Vec<String> = (0..size)
+        .map(|_| rng.random_range(0..2000).to_string())
+        .collect();
+    positions.join(",") + "\n"
+}
+
+/// Returns `size` notes of randomly wired seven-segment displays.
+pub fn day8(rng: &mut impl Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let wiring = random_wiring(rng);
+            let value = [0; 4].map(|_: u8| rng.random_range(0..10));
+            seven_segment_note(rng, &wiring, value) + "\n"
+        })
+        .collect()
+}
+
+/// Returns a random wiring of a seven-segment display.
+///
+/// The wire at index `i` is connected to segment `'a' + i`.
+pub fn random_wiring(rng: &mut impl Rng) -> [char; 7] {
+    let mut wiring = ['a', 'b', 'c', 'd', 'e', 'f', 'g'];
+    wiring.shuffle(rng);
+    wiring
+}
+
+/// Returns a note for a display with the given wiring, showing the given four digit value.
+///
+/// The ten patterns and the letters within every pattern are shuffled, like in the puzzle.
+pub fn seven_segment_note(rng: &mut impl Rng, wiring: &[char; 7], value: [u8; 4]) -> String {
+    let mut pattern = |digit: usize| -> String {
+        let mut wires: Vec<char> = DIGIT_SEGMENTS[digit]
+            .bytes()
+            .map(|s| wiring[(s - b'a') as usize])
+            .collect();
+        wires.shuffle(rng);
+        wires.into_iter().collect()
+    };
+    let mut patterns: Vec<String> = (0..10).map(&mut pattern).collect();
+    let output: Vec<String> = value.iter().map(|d| pattern(*d as usize)).collect();
+    patterns.shuffle(rng);
+    format!("{} | {}", patterns.join(" "), output.join(" "))
+}
+
+/// Returns a heightmap of `size` by `size` locations.
+pub fn day9(rng: &mut impl Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let row: String = (0..size)
+                .map(|_| char::from(b'0' + rng.random_range(0..10)))
+                .collect();
+            row + "\n"
+        })
+        .collect()
+}
+
+/// Returns `size` lines of navigation subsystem syntax. Every line is either corrupted or
+/// incomplete, and the number of incomplete lines is odd so the middle score exists.
+pub fn day10(rng: &mut impl Rng, size: usize) -> String {
+    let incomplete = (size / 2) | 1;
+    let mut corrupted: Vec<bool> = (0..size).map(|i| i >= incomplete).collect();
+    corrupted.shuffle(rng);
+    corrupted
+        .into_iter()
+        .map(|c| bracket_line(rng, c) + "\n")
+        .collect()
+}
+
+/// Returns a line of chunks that is either corrupted, or incomplete with at least one open chunk.
+fn bracket_line(rng: &mut impl Rng, corrupted: bool) -> String {
+    let mut line = String::new();
+    let mut open = vec![];
+
+    for _ in 0..rng.random_range(10..=100) {
+        if !open.is_empty() && (open.len() >= MAX_CHUNK_DEPTH || rng.random_bool(0.45)) {
+            line.push(open.pop().unwrap());
+        } else {
+            open_chunk(rng, &mut line, &mut open);
+        }
+    }
+    if open.is_empty() {
+        open_chunk(rng, &mut line, &mut open);
+    }
+
+    if corrupted {
+        // Close the last chunk with a wrong character.
+        let expected = *open.last().unwrap();
+        let wrong: Vec<char> = CHUNK_PAIRS
+            .iter()
+            .map(|(_, c)| *c)
+            .filter(|c| *c != expected)
+            .collect();
+        line.push(wrong[rng.random_range(0..wrong.len())]);
+    }
+    line
+}
+
+/// Adds the opening character of a random chunk to the line, and keeps track of its closing
+/// character.
+fn open_chunk(rng: &mut impl Rng, line: &mut String, open: &mut Vec<char>) {
+    let (opening, closing) = CHUNK_PAIRS[rng.random_range(0..CHUNK_PAIRS.len())];
+    line.push(opening);
+    open.push(closing);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{self, Part};
+
+    #[test]
+    fn test_inputs_are_valid() {
+        for puzzle in runner::puzzles() {
+            for seed in 0..10 {
+                let input = input(puzzle.day, 20, seed).unwrap();
+                let parsed = puzzle
+                    .parse(&input)
+                    .unwrap_or_else(|e| panic!("{}\n{}", e, input));
+                for part in Part::ALL {
+                    assert!(
+                        puzzle.solve(part, &parsed).is_ok(),
+                        "day {}, part {} failed for seed {}",
+                        puzzle.day,
+                        part,
+                        seed
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_streaming() {
+        #[cfg(feature = "day1")]
+        assert_streaming::<crate::day1::Day1>();
+        #[cfg(feature = "day2")]
+        assert_streaming::<crate::day2::Day2>();
+        #[cfg(feature = "day3")]
+        assert_streaming::<crate::day3::Day3>();
+        #[cfg(feature = "day6")]
+        assert_streaming::<crate::day6::Day6>();
+        #[cfg(feature = "day7")]
+        assert_streaming::<crate::day7::Day7>();
+        #[cfg(feature = "day10")]
+        assert_streaming::<crate::day10::Day10>();
+    }
+
+    /// Checks that reading generated inputs line by line gives the same answers as parsing them.
+    #[cfg(any(
+        feature = "day1",
+        feature = "day2",
+        feature = "day3",
+        feature = "day6",
+        feature = "day7",
+        feature = "day10"
+    ))]
+    fn assert_streaming<S: crate::Streaming>()
+    where
+        S::Answer1: PartialEq + std::fmt::Debug,
+        S::Answer2: PartialEq + std::fmt::Debug,
+    {
+        for seed in 0..10 {
+            let input = input(S::DAY, 50, seed).unwrap();
+            let parsed = S::parse(&input).unwrap();
+            let summary = S::summarize(input.as_bytes()).unwrap();
+            assert_eq!(S::part1(&parsed), S::summary_part1(&summary), "{}", input);
+            assert_eq!(S::part2(&parsed), S::summary_part2(&summary), "{}", input);
+        }
+    }
+
+    /// A quick version of the fuzz targets in `fuzz/`: the generators must reject damaged inputs
+    /// with an error instead of panicking.
+    #[test]
+    fn test_damaged_inputs() {
+        let noise: Vec<char> = "0123456789 ,->|ag([{<}]\n\r\té€".chars().collect();
+        for puzzle in runner::puzzles() {
+            for seed in 0..200 {
+                let rng = &mut StdRng::seed_from_u64(seed);
+                let mut chars: Vec<char> = input(puzzle.day, 3, seed).unwrap().chars().collect();
+                for _ in 0..rng.random_range(1..=3) {
+                    let i = rng.random_range(0..=chars.len());
+                    match rng.random_range(0..3) {
+                        0 => chars.insert(i, noise[rng.random_range(0..noise.len())]),
+                        1 if i < chars.len() => {
+                            chars.remove(i);
+                        }
+                        _ => chars.truncate(i),
+                    }
+                }
+                let damaged: String = chars.into_iter().collect();
+                let result = std::panic::catch_unwind(|| puzzle.parse(&damaged).is_ok());
+                assert!(result.is_ok(), "day {} panics on {:?}", puzzle.day, damaged);
+            }
+        }
+    }
+
+    #[test]
+    fn test_seed() {
+        assert_eq!(input(9, 10, 42), input(9, 10, 42));
+        assert_ne!(input(9, 10, 42), input(9, 10, 43));
+        assert_eq!(None, input(25, 10, 42));
+    }
+
+    #[test]
+    fn test_seven_segment_note() {
+        let rng = &mut StdRng::seed_from_u64(0);
+        let wiring = ['a', 'b', 'c', 'd', 'e', 'f', 'g'];
+        let note = seven_segment_note(rng, &wiring, [1, 4, 7, 8]);
+        let (patterns, output) = note.split_once(" | ").unwrap();
+        assert_eq!(10, patterns.split(' ').count());
+        let lengths: Vec<usize> = output.split(' ').map(str::len).collect();
+        assert_eq!(vec![2, 4, 3, 7], lengths);
+    }
+}
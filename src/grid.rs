@@ -0,0 +1,296 @@
+use crate::error::{end_of_input, ParseError, Span};
+use std::ops::{Index, IndexMut};
+
+/// Offsets of the orthogonal neighbours: up, down, left and right.
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+
+/// Offsets of the orthogonal and diagonal neighbours, in reading order.
+const ALL_DIRECTIONS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A rectangular two-dimensional grid.
+///
+/// The cells are stored in a flat vector in row-major order. Positions are expressed as `(x, y)`
+/// where `x` is the column and `y` is the row, starting from the top left corner.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid of the given size with all cells set to `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of cells does not fit in a `usize`.
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        let size = width.checked_mul(height).expect("grid size overflows");
+        Self {
+            width,
+            height,
+            cells: vec![value; size],
+        }
+    }
+
+    /// Creates a grid from cells in row-major order.
+    ///
+    /// Returns `None` if the number of cells is not a multiple of the width.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Option<Self> {
+        let height = cells.len().checked_div(width).unwrap_or(0);
+        (width * height == cells.len()).then_some(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses a block of text into a grid, converting every character with `f`.
+    ///
+    /// All lines should have the same length. Characters for which `f` returns `None` are
+    /// reported as invalid characters for the given day.
+    pub fn parse(day: u8, input: &str, f: impl Fn(char) -> Option<T>) -> Result<Self, ParseError> {
+        let mut cells = vec![];
+        let mut width = 0;
+        let mut height = 0;
+        for (i, l) in input.lines().enumerate() {
+            let span = Span::line(day, i, l);
+            for (j, c) in l.char_indices() {
+                cells.push(f(c).ok_or_else(|| span.char_at(j, c).invalid_character())?);
+            }
+            let length = l.chars().count();
+            if length == 0 || (height > 0 && length != width) {
+                return Err(span.invalid_format());
+            }
+            width = length;
+            height += 1;
+        }
+        if height == 0 {
+            return Err(end_of_input(day, input));
+        }
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Checks whether the given position lies within the grid.
+    pub const fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if self.contains(x, y) {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if self.contains(x, y) {
+            self.cells.get_mut(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over all cells in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// Returns an iterator over all positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Returns the position of the first cell in row-major order that matches the predicate.
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(|i| (i % self.width, i / self.width))
+    }
+
+    /// Returns the positions of the up to 4 orthogonal neighbours of the given position.
+    ///
+    /// The iterator does not borrow the grid, so it can be used while the grid is being modified.
+    pub fn neighbours(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.offsets(x, y, &ORTHOGONAL)
+    }
+
+    /// Returns the positions of the up to 8 orthogonal and diagonal neighbours of the given
+    /// position.
+    pub fn all_neighbours(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.offsets(x, y, &ALL_DIRECTIONS)
+    }
+
+    fn offsets(
+        &self,
+        x: usize,
+        y: usize,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let nx = x.checked_add_signed(*dx)?;
+            let ny = y.checked_add_signed(*dy)?;
+            (nx < width && ny < height).then_some((nx, ny))
+        })
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// Returns a grid of the same size with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Returns a copy of the grid with rows and columns swapped.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width: self.height,
+            height: self.width,
+            cells: self.columns().flatten().cloned().collect(),
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y)
+            .unwrap_or_else(|| panic!("position ({}, {}) is outside of the grid", x, y))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("position ({}, {}) is outside of the grid", x, y))
+    }
+}
+
+impl<T, const W: usize, const H: usize> From<[[T; W]; H]> for Grid<T> {
+    fn from(rows: [[T; W]; H]) -> Self {
+        Self {
+            width: W,
+            height: H,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse(0, get_test_input(), |c| c.to_digit(10)).unwrap();
+        assert_eq!(Grid::from([[1, 2, 3], [4, 5, 6]]), grid);
+        assert_eq!(3, grid.width());
+        assert_eq!(2, grid.height());
+        assert_eq!(Some(&6), grid.get(2, 1));
+        assert_eq!(None, grid.get(3, 1));
+
+        assert_eq!(
+            Err(ParseError::InvalidFormat {
+                day: 0,
+                line: 2,
+                column: 1,
+                text: String::from("45"),
+            }),
+            Grid::parse(0, "123\n45\n", |c| c.to_digit(10))
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(
+            vec![(1, 0), (1, 2), (0, 1), (2, 1)],
+            grid.neighbours(1, 1).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(0, 1), (1, 0)],
+            grid.neighbours(0, 0).collect::<Vec<_>>()
+        );
+        assert_eq!(8, grid.all_neighbours(1, 1).count());
+        assert_eq!(
+            vec![(1, 1), (2, 1), (1, 2)],
+            grid.all_neighbours(2, 2).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = Grid::from([[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(
+            vec![&[1, 2, 3][..], &[4, 5, 6][..]],
+            grid.rows().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![vec![1, 4], vec![2, 5], vec![3, 6]],
+            grid.columns()
+                .map(|c| c.copied().collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Grid::from([[1, 4], [2, 5], [3, 6]]), grid.transpose());
+        assert_eq!(Some((1, 1)), grid.position(|v| *v == 5));
+        assert_eq!(Grid::from([[2, 4, 6], [8, 10, 12]]), grid.map(|v| v * 2));
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+            123
+            456
+        "}
+    }
+}
@@ -1,14 +1,47 @@
 use aoc_runner_derive::aoc_lib;
 
-mod day1;
-mod day10;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
+pub use answer::Answer;
+pub use error::ParseError;
+pub use runner::{puzzle, puzzles, Part, Puzzle};
+pub use solution::{Solution, Streaming, Trace, Visualize, Windowed};
+pub use stream::StreamError;
+
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod cache;
+#[cfg(feature = "day1")]
+pub mod day1;
+#[cfg(feature = "day10")]
+pub mod day10;
+#[cfg(feature = "day2")]
+pub mod day2;
+#[cfg(feature = "day3")]
+pub mod day3;
+#[cfg(feature = "day4")]
+pub mod day4;
+#[cfg(feature = "day5")]
+pub mod day5;
+#[cfg(feature = "day6")]
+pub mod day6;
+#[cfg(feature = "day7")]
+pub mod day7;
+#[cfg(feature = "day8")]
+pub mod day8;
+#[cfg(feature = "day9")]
+pub mod day9;
+pub mod example;
+pub mod gen;
+pub mod grid;
+pub mod reference;
+pub mod runner;
+pub mod trace;
+pub mod viz;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+mod error;
+mod solution;
+mod stream;
 
 aoc_lib! { year = 2021 }
@@ -1,3 +1,1018 @@
-use aoc_runner_derive::aoc_main;
+use advent_of_code_2021::answer::Answer;
+use advent_of_code_2021::answers::{Answers, Verdict};
+use advent_of_code_2021::bench::{self, Report};
+use advent_of_code_2021::cache::{self, Cache};
+#[cfg(feature = "day1")]
+use advent_of_code_2021::day1::{self, DepthProfile, Increases, OutlierFilter, Outliers};
+use advent_of_code_2021::gen;
+use advent_of_code_2021::runner::{self, Input, Inputs, Part, Puzzle, RunError};
+use advent_of_code_2021::trace::Terminal;
+#[cfg(feature = "day1")]
+use advent_of_code_2021::StreamError;
+use clap::builder::RangedU64ValueParser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
 
-aoc_main! { lib = advent_of_code_2021 }
+/// Solutions for the Advent of Code 2021.
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve the puzzle of one day, or of all days.
+    Run(RunArgs),
+    /// Solve all days and compare the answers with the recorded answers.
+    Verify(VerifyArgs),
+    /// Time the generator and both parts of every day.
+    Bench(BenchArgs),
+    /// Print a random input for a day.
+    Generate(GenerateArgs),
+    /// Print statistics of the depth measurements of day 1.
+    #[cfg(feature = "day1")]
+    Sonar(SonarArgs),
+}
+
+#[derive(Args)]
+struct InputArgs {
+    /// The directory containing the puzzle inputs and the recorded answers.
+    #[arg(long, value_name = "DIR", default_value = runner::INPUT_DIR)]
+    input_dir: PathBuf,
+
+    /// Use the inputs of this profile, which are stored in `<DIR>/<PROFILE>/2021/`.
+    #[arg(long)]
+    profile: Option<String>,
+}
+
+impl InputArgs {
+    fn inputs(&self) -> Inputs {
+        Inputs::new(&self.input_dir, self.profile.clone())
+    }
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// The day to solve.
+    #[arg(short, long, required_unless_present = "all")]
+    day: Option<u8>,
+
+    /// Solve all days.
+    #[arg(short, long, conflicts_with_all = ["day", "input"])]
+    all: bool,
+
+    /// Only solve the given part.
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// The input file, or `-` to read from standard input. Defaults to `input/2021/dayN.txt`.
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    #[command(flatten)]
+    inputs: InputArgs,
+
+    /// Solve the inputs of all profiles, and print the answers side by side.
+    #[arg(
+        long,
+        conflicts_with_all = ["input", "profile", "stream", "visualize", "trace", "window"]
+    )]
+    all_profiles: bool,
+
+    /// Read the input line by line instead of loading it in memory, for the days that support it.
+    #[arg(long)]
+    stream: bool,
+
+    /// Render the puzzles that have a visualization to images in this directory.
+    #[arg(long, value_name = "OUT_DIR", conflicts_with = "stream")]
+    visualize: Option<PathBuf>,
+
+    /// Print the intermediate state of the solver at every step, for the days that support it.
+    #[arg(long, conflicts_with = "stream")]
+    trace: bool,
+
+    /// Play the trace as an animation, waiting this many milliseconds between frames.
+    #[arg(long, value_name = "MS", requires = "trace")]
+    trace_delay: Option<u64>,
+
+    /// Solve the days and their parts at the same time on this many threads. 0 uses a thread per
+    /// CPU.
+    #[arg(
+        short,
+        long,
+        value_name = "N",
+        conflicts_with_all = ["all_profiles", "stream", "visualize", "trace", "window"]
+    )]
+    jobs: Option<usize>,
+
+    /// Solve part 2 with windows of this many values, for the days that compare sliding windows.
+    /// The answers are not cached.
+    #[arg(
+        long,
+        value_name = "SIZE",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        conflicts_with_all = ["stream", "visualize", "trace"]
+    )]
+    window: Option<usize>,
+
+    /// Solve the puzzles again, even if their answers are cached.
+    #[arg(long)]
+    force: bool,
+
+    /// The format of the answers. JSON includes the timings of every part.
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = AnswerFormat::Text,
+        conflicts_with_all = ["all_profiles", "visualize", "trace"]
+    )]
+    format: AnswerFormat,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// The file containing the recorded answers. Defaults to `input/2021/answers.txt`.
+    #[arg(long)]
+    answers: Option<PathBuf>,
+
+    #[command(flatten)]
+    inputs: InputArgs,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    /// Only benchmark the given day.
+    #[arg(short, long)]
+    day: Option<u8>,
+
+    /// The number of timed runs of every phase.
+    #[arg(short, long, default_value_t = 10)]
+    runs: usize,
+
+    /// The format of the report.
+    #[arg(short, long, value_enum, default_value_t = ReportFormat::Text)]
+    format: ReportFormat,
+
+    /// Write the report to this file instead of standard output.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    #[command(flatten)]
+    inputs: InputArgs,
+}
+
+#[derive(Args)]
+struct GenerateArgs {
+    /// The day to generate an input for.
+    #[arg(short, long)]
+    day: u8,
+
+    /// The amount of input, e.g. the number of lines.
+    #[arg(short, long, default_value_t = 100)]
+    size: usize,
+
+    /// The seed of the random number generator.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+#[cfg(feature = "day1")]
+#[derive(Args)]
+struct SonarArgs {
+    /// The input file, or `-` to read from standard input. Defaults to `input/2021/day1.txt`.
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    #[command(flatten)]
+    inputs: InputArgs,
+
+    /// The format of the statistics.
+    #[arg(short, long, value_enum, default_value_t = AnswerFormat::Text)]
+    format: AnswerFormat,
+
+    /// Discard glitches before counting the increases again, and report the discarded readings.
+    #[arg(long)]
+    filter: bool,
+
+    /// Print the running increases of both parts after every reading, instead of the statistics.
+    /// Readings are counted as they arrive, so the input can be a pipe that is still written to.
+    #[arg(long, conflicts_with = "filter")]
+    follow: bool,
+
+    /// The number of readings on either side that a reading is compared with by the filter.
+    #[arg(long, value_name = "N", default_value_t = OutlierFilter::default().radius, requires = "filter")]
+    radius: usize,
+
+    /// The number of standard deviations a reading may differ from the median of its neighbours.
+    #[arg(long, value_name = "K", default_value_t = OutlierFilter::default().threshold, requires = "filter")]
+    threshold: f64,
+
+    /// The difference from the median that a reading is always allowed by the filter, e.g. the
+    /// size of the normal changes in depth, so they are kept on flat stretches.
+    #[arg(
+        long,
+        value_name = "DEPTH",
+        default_value_t = OutlierFilter::default().min_deviation,
+        requires = "filter"
+    )]
+    min_deviation: f64,
+}
+
+/// The output of the sonar command.
+#[cfg(feature = "day1")]
+#[derive(Serialize)]
+struct SonarReport {
+    #[serde(flatten)]
+    profile: DepthProfile,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outliers: Option<Outliers>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum AnswerFormat {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    // Solver panics are caught and reported by the runner, so the default panic message would only
+    // be noise. Panics elsewhere are bugs in the runner, and are still printed.
+    runner::silence_solver_panics();
+
+    match cli.command {
+        Command::Run(args) => run(&args),
+        Command::Verify(args) => verify(&args),
+        Command::Bench(args) => bench(&args),
+        Command::Generate(args) => generate(&args),
+        #[cfg(feature = "day1")]
+        Command::Sonar(args) => sonar(&args),
+    }
+}
+
+fn run(args: &RunArgs) -> ExitCode {
+    let puzzles: Vec<&Puzzle> = match args.day {
+        Some(day) => match runner::puzzle(day) {
+            Some(puzzle) if args.stream && !puzzle.can_stream() => {
+                eprintln!("error: day {} cannot stream its input", day);
+                return ExitCode::from(2);
+            }
+            Some(puzzle) if args.visualize.is_some() && !puzzle.can_visualize() => {
+                eprintln!("error: day {} has no visualization", day);
+                return ExitCode::from(2);
+            }
+            Some(puzzle) if args.trace && !puzzle.can_trace() => {
+                eprintln!("error: day {} cannot be traced", day);
+                return ExitCode::from(2);
+            }
+            Some(puzzle) if args.window.is_some() && !puzzle.can_window() => {
+                eprintln!("error: day {} does not compare windows", day);
+                return ExitCode::from(2);
+            }
+            Some(puzzle) => vec![puzzle],
+            None => {
+                eprintln!("error: day {} has not been solved", day);
+                return ExitCode::from(2);
+            }
+        },
+        None => runner::puzzles().iter().collect(),
+    };
+    let parts = match args.part.and_then(Part::from_number) {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    };
+    let mut cache = Cache::load(Path::new(cache::CACHE_PATH));
+    let status = if args.all_profiles {
+        run_profiles(
+            &args.inputs.input_dir,
+            &puzzles,
+            &parts,
+            &mut cache,
+            args.force,
+        )
+    } else {
+        let mut results = Results::new(args.format);
+        match args.jobs {
+            Some(jobs) => run_concurrently(&puzzles, &parts, args, &mut cache, jobs, &mut results),
+            None => run_puzzles(&puzzles, &parts, args, &mut cache, &mut results),
+        }
+        results.finish()
+    };
+    // The answers have been printed already, so a cache that cannot be written is not a failure.
+    if let Err(error) = cache.save() {
+        eprintln!("warning: {}", error);
+    }
+    ExitCode::from(status)
+}
+
+/// Solves the puzzles one by one. The answers are added to the results as soon as a puzzle is
+/// solved.
+fn run_puzzles(
+    puzzles: &[&Puzzle],
+    parts: &[Part],
+    args: &RunArgs,
+    cache: &mut Cache,
+    results: &mut Results,
+) {
+    let inputs = args.inputs.inputs();
+    for puzzle in puzzles {
+        let path = args
+            .input
+            .clone()
+            .unwrap_or_else(|| inputs.input_path(puzzle.day));
+        if args.stream && puzzle.can_stream() {
+            solve_streamed(puzzle, &path, parts, results);
+        } else if let (Some(size), true) = (args.window, puzzle.can_window()) {
+            solve_windowed(puzzle, &path, parts, size, results);
+        } else {
+            solve(puzzle, &path, parts, args, cache, results);
+        }
+    }
+}
+
+/// Solves the puzzles and both of their parts at the same time on `jobs` threads. The answers are
+/// added to the results in order once every puzzle has been solved.
+fn run_concurrently(
+    puzzles: &[&Puzzle],
+    parts: &[Part],
+    args: &RunArgs,
+    cache: &mut Cache,
+    jobs: usize,
+    results: &mut Results,
+) {
+    let pool = match ThreadPoolBuilder::new().num_threads(jobs).build() {
+        Ok(pool) => pool,
+        Err(error) => {
+            eprintln!("error: cannot start {} threads: {}", jobs, error);
+            results.status = 3;
+            return;
+        }
+    };
+    let inputs = args.inputs.inputs();
+    let shared: &Cache = cache;
+    let solved: Vec<_> = pool.install(|| {
+        puzzles
+            .par_iter()
+            .map(|puzzle| {
+                let path = args
+                    .input
+                    .clone()
+                    .unwrap_or_else(|| inputs.input_path(puzzle.day));
+                let text = runner::read_input(&path)?;
+                let fingerprint = cache::fingerprint(text.as_bytes());
+                let solved = solve_parts(
+                    puzzle,
+                    &text,
+                    &mut None,
+                    parts,
+                    shared,
+                    fingerprint,
+                    args.force,
+                    true,
+                )?;
+                Ok((fingerprint, solved))
+            })
+            .collect()
+    });
+
+    for (puzzle, result) in puzzles.iter().zip(solved) {
+        match result {
+            Ok((fingerprint, solved)) => {
+                cache_answers(cache, puzzle, fingerprint, &solved);
+                results.add(puzzle.day, solved);
+            }
+            Err(error) => results.fail(puzzle.day, None, error),
+        }
+    }
+}
+
+/// Solves the puzzles for the inputs of all profiles in the directory, and prints the answers in a
+/// table with a column per profile. Returns the exit code.
+fn run_profiles(
+    dir: &Path,
+    puzzles: &[&Puzzle],
+    parts: &[Part],
+    cache: &mut Cache,
+    force: bool,
+) -> u8 {
+    let profiles = match Inputs::profiles(dir) {
+        Ok(profiles) if profiles.is_empty() => {
+            eprintln!("error: there are no inputs in {}", dir.display());
+            return 3;
+        }
+        Ok(profiles) => profiles,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return error.exit_code();
+        }
+    };
+
+    let mut header = vec![String::from("Day"), String::from("Part")];
+    header.extend(
+        profiles
+            .iter()
+            .map(|inputs| inputs.profile().unwrap_or("default").to_string()),
+    );
+    let mut table = vec![header];
+    let mut status = 0;
+    for puzzle in puzzles {
+        let mut rows: Vec<Vec<String>> = parts
+            .iter()
+            .map(|part| vec![puzzle.day.to_string(), part.to_string()])
+            .collect();
+        for inputs in &profiles {
+            let profile = inputs.profile().unwrap_or("default");
+            let mut fail = |error: RunError| {
+                eprintln!("error: {}: {}", profile, error);
+                if status == 0 {
+                    status = error.exit_code();
+                }
+                String::from("error")
+            };
+            let cells: Vec<String> = match solve_profile(puzzle, inputs, parts, cache, force) {
+                Ok(Some(answers)) => answers
+                    .into_iter()
+                    .map(|answer| answer.unwrap_or_else(&mut fail))
+                    .collect(),
+                Ok(None) => vec![String::from("-"); parts.len()],
+                Err(error) => vec![fail(error); parts.len()],
+            };
+            for (row, cell) in rows.iter_mut().zip(cells) {
+                row.push(cell);
+            }
+        }
+        table.extend(rows);
+    }
+
+    let widths: Vec<usize> = (0..table[0].len())
+        .map(|i| table.iter().map(|row| row[i].len()).max().unwrap_or(0))
+        .collect();
+    for row in table {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+        println!("{}", cells.join("  ").trim_end());
+    }
+    status
+}
+
+/// Solves the given parts of a puzzle for the input of a profile. Returns `None` if the profile
+/// has no input for the day.
+fn solve_profile(
+    puzzle: &Puzzle,
+    inputs: &Inputs,
+    parts: &[Part],
+    cache: &mut Cache,
+    force: bool,
+) -> Result<Option<Vec<Result<String, RunError>>>, RunError> {
+    let text = match runner::read_input(&inputs.input_path(puzzle.day)) {
+        Ok(text) => text,
+        Err(RunError::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound => {
+            return Ok(None);
+        }
+        Err(error) => return Err(error),
+    };
+    let answers = solve_cached(puzzle, &text, &mut None, parts, cache, force)?
+        .into_iter()
+        .map(|solved| Ok(solved.answer?.to_record()))
+        .collect();
+    Ok(Some(answers))
+}
+
+/// Solves the given parts of a puzzle and adds the answers to the results. The puzzle is also
+/// rendered or traced if requested.
+fn solve(
+    puzzle: &Puzzle,
+    path: &Path,
+    parts: &[Part],
+    args: &RunArgs,
+    cache: &mut Cache,
+    results: &mut Results,
+) {
+    let text = match runner::read_input(path) {
+        Ok(text) => text,
+        Err(error) => return results.fail(puzzle.day, None, error),
+    };
+    let mut input = None;
+    if args.trace || args.visualize.is_some() {
+        match puzzle.parse(&text) {
+            Ok(parsed) => input = Some(parsed),
+            Err(error) => return results.fail(puzzle.day, None, error.into()),
+        }
+    }
+    if let (true, Some(input)) = (args.trace, &input) {
+        let mut stdout = io::stdout().lock();
+        let delay = args.trace_delay.map(Duration::from_millis);
+        let mut terminal = Terminal::new(&mut stdout, delay);
+        if let Some(Err(error)) = puzzle.trace(input, &mut terminal) {
+            results.fail(puzzle.day, None, error);
+        }
+    }
+    let dir = args
+        .visualize
+        .as_ref()
+        .map(|dir| dir.join(format!("day{}", puzzle.day)));
+    let visualization = dir
+        .zip(input.as_ref())
+        .and_then(|(dir, input)| puzzle.visualize(input, &dir).map(|result| (dir, result)));
+    match visualization {
+        Some((dir, Ok(files))) => {
+            println!(
+                "Day {}: wrote {} image{} to {}",
+                puzzle.day,
+                files.len(),
+                if files.len() == 1 { "" } else { "s" },
+                dir.display()
+            )
+        }
+        Some((_, Err(error))) => results.fail(puzzle.day, None, error),
+        None => {}
+    }
+    match solve_cached(puzzle, &text, &mut input, parts, cache, args.force) {
+        Ok(solved) => results.add(puzzle.day, solved),
+        Err(error) => results.fail(puzzle.day, None, error),
+    }
+}
+
+/// The answer to a part of a puzzle, or the error that prevented it from being solved.
+struct Solved {
+    part: Part,
+    answer: Result<Answer, RunError>,
+    /// Whether the answer was taken from the cache.
+    cached: bool,
+    /// The time it took to parse the input, if it was parsed to find this answer.
+    generator_time: Option<Duration>,
+    /// The time it took to solve the part, if it was not cached.
+    solve_time: Option<Duration>,
+}
+
+/// Solves the given parts of a puzzle, returning cached answers unless `force` is set, and adds the
+/// new answers to the cache.
+fn solve_cached(
+    puzzle: &Puzzle,
+    text: &str,
+    input: &mut Option<Input>,
+    parts: &[Part],
+    cache: &mut Cache,
+    force: bool,
+) -> Result<Vec<Solved>, RunError> {
+    let fingerprint = cache::fingerprint(text.as_bytes());
+    let solved = solve_parts(puzzle, text, input, parts, cache, fingerprint, force, false)?;
+    cache_answers(cache, puzzle, fingerprint, &solved);
+    Ok(solved)
+}
+
+/// Solves the given parts of a puzzle, returning cached answers unless `force` is set. The input
+/// is only parsed if an answer is not cached and `input` does not hold the parsed input yet.
+///
+/// With `concurrent`, the parts are solved at the same time on the current rayon thread pool.
+#[allow(clippy::too_many_arguments)]
+fn solve_parts(
+    puzzle: &Puzzle,
+    text: &str,
+    input: &mut Option<Input>,
+    parts: &[Part],
+    cache: &Cache,
+    fingerprint: u64,
+    force: bool,
+    concurrent: bool,
+) -> Result<Vec<Solved>, RunError> {
+    let cached: Vec<Option<Answer>> = parts
+        .iter()
+        .map(|&part| {
+            cache
+                .get(puzzle, part, fingerprint)
+                .filter(|_| !force)
+                .cloned()
+        })
+        .collect();
+    let mut generator_time = None;
+    if input.is_none() && cached.iter().any(Option::is_none) {
+        let start = Instant::now();
+        *input = Some(puzzle.parse(text)?);
+        generator_time = Some(start.elapsed());
+    }
+    let input = &*input;
+    let solve = |(&part, cached): (&Part, Option<Answer>)| match cached {
+        Some(answer) => Solved {
+            part,
+            answer: Ok(answer),
+            cached: true,
+            generator_time: None,
+            solve_time: None,
+        },
+        None => {
+            let start = Instant::now();
+            let answer = puzzle.solve(part, input.as_ref().expect("the input is parsed"));
+            Solved {
+                part,
+                answer,
+                cached: false,
+                generator_time,
+                solve_time: Some(start.elapsed()),
+            }
+        }
+    };
+    Ok(if concurrent {
+        parts.par_iter().zip(cached).map(solve).collect()
+    } else {
+        parts.iter().zip(cached).map(solve).collect()
+    })
+}
+
+/// Adds the answers that were found for an input with the given fingerprint to the cache.
+fn cache_answers(cache: &mut Cache, puzzle: &Puzzle, fingerprint: u64, solved: &[Solved]) {
+    for solved in solved {
+        if let (Ok(answer), false) = (&solved.answer, solved.cached) {
+            cache.insert(puzzle, solved.part, fingerprint, answer.clone());
+        }
+    }
+}
+
+/// Solves the given parts of a puzzle from a summary of its input, and adds the answers to the
+/// results. The time it takes to read the summary is reported as the generator time.
+fn solve_streamed(puzzle: &Puzzle, path: &Path, parts: &[Part], results: &mut Results) {
+    let start = Instant::now();
+    let summary = match puzzle.summarize(path).expect("the puzzle can stream") {
+        Ok(summary) => summary,
+        Err(error) => return results.fail(puzzle.day, None, error),
+    };
+    let generator_time = Some(start.elapsed());
+    let solved = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = puzzle.solve_summary(part, &summary);
+            Solved {
+                part,
+                answer,
+                cached: false,
+                generator_time,
+                solve_time: Some(start.elapsed()),
+            }
+        })
+        .collect();
+    results.add(puzzle.day, solved);
+}
+
+/// Solves the given parts of a puzzle with windows of `size` values for part 2, and adds the
+/// answers to the results. The answers are not cached, since they differ from those of the puzzle.
+fn solve_windowed(
+    puzzle: &Puzzle,
+    path: &Path,
+    parts: &[Part],
+    size: usize,
+    results: &mut Results,
+) {
+    let start = Instant::now();
+    let input = match runner::read_input(path).and_then(|text| Ok(puzzle.parse(&text)?)) {
+        Ok(input) => input,
+        Err(error) => return results.fail(puzzle.day, None, error),
+    };
+    let generator_time = Some(start.elapsed());
+    let solved = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = match part {
+                Part::One => puzzle.solve(part, &input),
+                Part::Two => puzzle
+                    .solve_window(&input, size)
+                    .expect("the puzzle compares windows"),
+            };
+            Solved {
+                part,
+                answer,
+                cached: false,
+                generator_time,
+                solve_time: Some(start.elapsed()),
+            }
+        })
+        .collect();
+    results.add(puzzle.day, solved);
+}
+
+/// The result of solving a part of a puzzle, as printed by `run --format json`. Durations are in
+/// nanoseconds. An error that prevented a whole puzzle from being solved has no part.
+#[derive(Serialize)]
+struct Outcome {
+    day: u8,
+    part: Option<u8>,
+    answer: Option<String>,
+    cached: bool,
+    generator_time: Option<u64>,
+    solve_time: Option<u64>,
+    error: Option<String>,
+}
+
+/// Collects the answers of the run command and prints them in the requested format.
+///
+/// Text is printed as soon as an answer is added, while JSON is printed as a whole by `finish()`.
+/// The exit code is that of the first error, so the runner keeps going when a day fails.
+struct Results {
+    format: AnswerFormat,
+    outcomes: Vec<Outcome>,
+    status: u8,
+}
+
+impl Results {
+    fn new(format: AnswerFormat) -> Self {
+        Self {
+            format,
+            outcomes: vec![],
+            status: 0,
+        }
+    }
+
+    fn add(&mut self, day: u8, solved: Vec<Solved>) {
+        for solved in solved {
+            let nanos = |d: Duration| d.as_nanos() as u64;
+            match solved.answer {
+                Ok(answer) => match self.format {
+                    AnswerFormat::Text => {
+                        println!("Day {} - Part {}: {}", day, solved.part, answer)
+                    }
+                    AnswerFormat::Json => self.outcomes.push(Outcome {
+                        day,
+                        part: Some(solved.part.number()),
+                        answer: Some(answer.to_string()),
+                        cached: solved.cached,
+                        generator_time: solved.generator_time.map(nanos),
+                        solve_time: solved.solve_time.map(nanos),
+                        error: None,
+                    }),
+                },
+                Err(error) => self.fail(day, Some(solved.part), error),
+            }
+        }
+    }
+
+    fn fail(&mut self, day: u8, part: Option<Part>, error: RunError) {
+        if self.status == 0 {
+            self.status = error.exit_code();
+        }
+        match self.format {
+            AnswerFormat::Text => eprintln!("error: {}", error),
+            AnswerFormat::Json => self.outcomes.push(Outcome {
+                day,
+                part: part.map(Part::number),
+                answer: None,
+                cached: false,
+                generator_time: None,
+                solve_time: None,
+                error: Some(error.to_string()),
+            }),
+        }
+    }
+
+    /// Prints the JSON output, and returns the exit code.
+    fn finish(self) -> u8 {
+        if let AnswerFormat::Json = self.format {
+            let json = serde_json::to_string_pretty(&self.outcomes)
+                .expect("the results can always be serialized");
+            println!("{}", json);
+        }
+        self.status
+    }
+}
+
+fn verify(args: &VerifyArgs) -> ExitCode {
+    let inputs = args.inputs.inputs();
+    let path = args
+        .answers
+        .clone()
+        .unwrap_or_else(|| inputs.answers_path());
+    let answers = match Answers::load(&path) {
+        Ok(answers) => answers,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return ExitCode::from(error.exit_code());
+        }
+    };
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    let mut status = 0;
+    let mut fail = |error: RunError| {
+        println!("FAIL: {}", error);
+        if status == 0 {
+            status = error.exit_code();
+        }
+    };
+
+    for puzzle in runner::puzzles() {
+        let start = Instant::now();
+        let input = match runner::read_input(&inputs.input_path(puzzle.day)) {
+            Ok(input) => input,
+            Err(RunError::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound => {
+                println!("Day {}: missing input", puzzle.day);
+                missing += Part::ALL.len();
+                continue;
+            }
+            Err(error) => {
+                failed += Part::ALL.len();
+                fail(error);
+                continue;
+            }
+        };
+        let input = match puzzle.parse(&input) {
+            Ok(input) => input,
+            Err(error) => {
+                failed += Part::ALL.len();
+                fail(error.into());
+                continue;
+            }
+        };
+        let generator = start.elapsed();
+
+        for part in Part::ALL {
+            let start = Instant::now();
+            let answer = match puzzle.solve(part, &input) {
+                Ok(answer) => answer,
+                Err(error) => {
+                    failed += 1;
+                    fail(error);
+                    continue;
+                }
+            };
+            let timings = format!("generator: {:?}, runner: {:?}", generator, start.elapsed());
+            match answers.check(puzzle.day, part, &answer) {
+                Verdict::Pass => {
+                    passed += 1;
+                    println!(
+                        "Day {} - Part {}: pass {} ({})",
+                        puzzle.day, part, answer, timings
+                    );
+                }
+                Verdict::Missing => {
+                    missing += 1;
+                    println!(
+                        "Day {} - Part {}: missing answer, got {} ({})",
+                        puzzle.day, part, answer, timings
+                    );
+                }
+                Verdict::Fail { expected } => {
+                    failed += 1;
+                    fail(RunError::WrongAnswer {
+                        day: puzzle.day,
+                        part,
+                        expected,
+                        actual: answer,
+                    });
+                }
+            }
+        }
+    }
+
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+    ExitCode::from(status)
+}
+
+fn bench(args: &BenchArgs) -> ExitCode {
+    let puzzles: Vec<&Puzzle> = runner::puzzles()
+        .iter()
+        .filter(|p| args.day.is_none_or(|day| p.day == day))
+        .collect();
+    if puzzles.is_empty() {
+        eprintln!("error: day {} has not been solved", args.day.unwrap_or(0));
+        return ExitCode::from(2);
+    }
+
+    let mut report = Report::default();
+    let mut status = 0;
+    let inputs = args.inputs.inputs();
+    for puzzle in puzzles {
+        let measurements = runner::read_input(&inputs.input_path(puzzle.day))
+            .and_then(|input| bench::bench(puzzle, &input, args.runs));
+        match measurements {
+            Ok(measurements) => report.measurements.extend(measurements),
+            Err(error) => {
+                eprintln!("error: {}", error);
+                if status == 0 {
+                    status = error.exit_code();
+                }
+            }
+        }
+    }
+
+    let output = match args.format {
+        ReportFormat::Text => report.to_string(),
+        ReportFormat::Json => report.to_json() + "\n",
+        ReportFormat::Csv => report.to_csv(),
+    };
+    match &args.output {
+        Some(path) => {
+            if let Err(error) = fs::write(path, output) {
+                eprintln!("error: cannot write {}: {}", path.display(), error);
+                return ExitCode::from(3);
+            }
+        }
+        None => print!("{}", output),
+    }
+    ExitCode::from(status)
+}
+
+fn generate(args: &GenerateArgs) -> ExitCode {
+    match gen::input(args.day, args.size, args.seed) {
+        Some(input) => {
+            print!("{}", input);
+            ExitCode::SUCCESS
+        }
+        None => {
+            eprintln!("error: day {} has not been solved", args.day);
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(feature = "day1")]
+fn sonar(args: &SonarArgs) -> ExitCode {
+    let path = args
+        .input
+        .clone()
+        .unwrap_or_else(|| args.inputs.inputs().input_path(1));
+    if args.follow {
+        return follow_sonar(&path, args.format);
+    }
+    let measurements =
+        runner::read_input(&path).and_then(|input| Ok(day1::parse_input_day1(&input)?));
+    let measurements = match measurements {
+        Ok(measurements) => measurements,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return ExitCode::from(error.exit_code());
+        }
+    };
+    let Some(profile) = DepthProfile::new(&measurements) else {
+        eprintln!("error: {} contains no measurements", path.display());
+        return ExitCode::from(4);
+    };
+    let filter = OutlierFilter {
+        radius: args.radius,
+        threshold: args.threshold,
+        min_deviation: args.min_deviation,
+    };
+    let report = SonarReport {
+        profile,
+        outliers: args.filter.then(|| filter.clean(&measurements)),
+    };
+    match args.format {
+        AnswerFormat::Text => {
+            print!("{}", report.profile);
+            if let Some(outliers) = &report.outliers {
+                print!("{}", outliers);
+            }
+        }
+        AnswerFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("a report can always be serialized")
+        ),
+    }
+    ExitCode::SUCCESS
+}
+
+/// Prints the running increases of day 1 after every reading of the input, as a line of text or
+/// JSON.
+#[cfg(feature = "day1")]
+fn follow_sonar(path: &Path, format: AnswerFormat) -> ExitCode {
+    let print = |increases: Increases| match format {
+        AnswerFormat::Text => println!(
+            "Part 1: {}, Part 2: {}",
+            increases.measurements, increases.windows
+        ),
+        AnswerFormat::Json => println!(
+            "{}",
+            serde_json::to_string(&increases).expect("increases can always be serialized")
+        ),
+    };
+    let result = runner::open_input(path)
+        .map_err(StreamError::from)
+        .and_then(|reader| day1::follow(reader, print));
+    match result {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(error) => {
+            let error = RunError::from_stream(path, error);
+            eprintln!("error: {}", error);
+            ExitCode::from(error.exit_code())
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Slow but straightforward reference solutions for differential testing.
+//!
+//! The solutions of some days rely on shortcuts that are easy to get subtly wrong. The functions in
+//! this module solve the same puzzles the naive way, so their answers can be compared with the
+//! optimised solutions on generated inputs.
+
+#[cfg(feature = "day8")]
+use crate::gen::DIGIT_SEGMENTS;
+#[cfg(feature = "day8")]
+use itertools::Itertools;
+
+/// Returns the number of lanternfish after the given number of days, by simulating every fish.
+///
+/// The school grows exponentially, so this is only feasible for small schools and up to about 100
+/// days.
+pub fn lanternfish(timers: &[u8], days: usize) -> usize {
+    let mut school = timers.to_vec();
+    for _ in 0..days {
+        let mut newborn = 0;
+        for timer in school.iter_mut() {
+            if *timer == 0 {
+                *timer = 6;
+                newborn += 1;
+            } else {
+                *timer -= 1;
+            }
+        }
+        school.extend(std::iter::repeat_n(8, newborn));
+    }
+    school.len()
+}
+
+/// Returns the least fuel the crabs need to align, by trying every position between the leftmost
+/// and the rightmost crab.
+///
+/// The cost function returns the fuel needed to move a crab the given distance.
+pub fn crab_fuel(positions: &[usize], cost: impl Fn(usize) -> usize) -> usize {
+    let (Some(&min), Some(&max)) = (positions.iter().min(), positions.iter().max()) else {
+        return 0;
+    };
+    (min..=max)
+        .map(|target| {
+            positions
+                .iter()
+                .map(|&p| cost(p.abs_diff(target)))
+                .sum::<usize>()
+        })
+        .min()
+        .unwrap()
+}
+
+/// Returns the wiring of a seven-segment display that produces the given patterns, by trying all
+/// 5040 wirings.
+///
+/// The wiring has the same format as `gen::random_wiring()`: segment `'a' + i` is connected to the
+/// wire at index `i`.
+#[cfg(feature = "day8")]
+pub fn find_wiring(patterns: &[String]) -> Option<[char; 7]> {
+    let mut observed: Vec<u8> = patterns.iter().map(|p| mask(p)).collect();
+    observed.sort_unstable();
+    ('a'..='g').permutations(7).find_map(|wires| {
+        let wiring: [char; 7] = wires.try_into().unwrap();
+        let mut expected: Vec<u8> = (0..10).map(|d| digit_mask(&wiring, d)).collect();
+        expected.sort_unstable();
+        (expected == observed).then_some(wiring)
+    })
+}
+
+/// Returns the value shown on the four digits of a note, or `None` if no wiring matches.
+#[cfg(feature = "day8")]
+pub fn decode_note(patterns: &[String], digits: &[String]) -> Option<usize> {
+    let wiring = find_wiring(patterns)?;
+    digits.iter().try_fold(0, |value, digit| {
+        let d = (0..10).find(|&d| digit_mask(&wiring, d) == mask(digit))?;
+        Some(value * 10 + d)
+    })
+}
+
+/// Returns the wires that light up the given digit.
+#[cfg(feature = "day8")]
+fn digit_mask(wiring: &[char; 7], digit: usize) -> u8 {
+    DIGIT_SEGMENTS[digit]
+        .bytes()
+        .map(|s| 1 << (wiring[(s - b'a') as usize] as u8 - b'a'))
+        .fold(0, |mask, bit| mask | bit)
+}
+
+/// Returns the wires in a pattern as a bit mask.
+#[cfg(feature = "day8")]
+fn mask(pattern: &str) -> u8 {
+    pattern.bytes().fold(0, |mask, c| mask | 1 << (c - b'a'))
+}
+
+// The tests only exist for the days that use the reference solutions.
+#[cfg(all(test, any(feature = "day6", feature = "day7", feature = "day8")))]
+mod tests {
+    use super::*;
+    #[cfg(feature = "day6")]
+    use crate::day6;
+    #[cfg(feature = "day7")]
+    use crate::day7;
+    #[cfg(feature = "day8")]
+    use crate::day8;
+    use crate::gen;
+    #[cfg(feature = "day8")]
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    #[cfg(feature = "day6")]
+    fn test_lanternfish() {
+        assert_eq!(26, lanternfish(&[3, 4, 3, 1, 2], 18));
+        assert_eq!(5934, lanternfish(&[3, 4, 3, 1, 2], 80));
+
+        for seed in 0..20 {
+            let input = gen::input(6, 5, seed).unwrap();
+            let timers: Vec<u8> = input
+                .trim()
+                .split(',')
+                .map(|t| t.parse().unwrap())
+                .collect();
+            let school = day6::parse_input(&input).unwrap();
+            for days in [0, 1, 7, 9, 40, 80] {
+                assert_eq!(
+                    lanternfish(&timers, days) as u64,
+                    day6::count_school(&school, days),
+                    "{} days, input {}",
+                    days,
+                    input
+                );
+            }
+            assert_eq!(lanternfish(&timers, 80) as u64, day6::part1(&school));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "day7")]
+    fn test_crab_fuel() {
+        for seed in 0..20 {
+            for size in [1, 2, 3, 20] {
+                let input = gen::input(7, size, seed).unwrap();
+                let positions = day7::parse_input(&input).unwrap();
+                assert_eq!(crab_fuel(&positions, |d| d), day7::part1(&positions));
+                assert_eq!(
+                    crab_fuel(&positions, |d| d * (d + 1) / 2),
+                    day7::part2(&positions),
+                    "input {}",
+                    input
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "day8")]
+    fn test_find_wiring() {
+        for seed in 0..20 {
+            let rng = &mut StdRng::seed_from_u64(seed);
+            let wiring = gen::random_wiring(rng);
+            let note = gen::seven_segment_note(rng, &wiring, [1, 9, 0, 2]);
+            let notes = day8::parse_input(&note).unwrap();
+            let (patterns, digits) = &notes[0];
+            assert_eq!(Some(wiring), find_wiring(patterns));
+            assert_eq!(Some(1902), decode_note(patterns, digits));
+        }
+
+        let patterns: Vec<String> = ["ab", "abc", "abcd", "abcde", "abcdef", "abcdefg"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        assert_eq!(None, find_wiring(&patterns));
+    }
+
+    #[test]
+    #[cfg(feature = "day8")]
+    fn test_decode_note() {
+        for seed in 0..5 {
+            let input = gen::input(8, 10, seed).unwrap();
+            let notes = day8::parse_input(&input).unwrap();
+            let expected: usize = notes
+                .iter()
+                .map(|(patterns, digits)| decode_note(patterns, digits).unwrap())
+                .sum();
+            assert_eq!(expected, day8::part2(&notes));
+        }
+    }
+}
@@ -0,0 +1,723 @@
+use crate::answer::Answer;
+use crate::cache;
+#[cfg(feature = "day1")]
+use crate::day1::Day1;
+#[cfg(feature = "day10")]
+use crate::day10::Day10;
+#[cfg(feature = "day2")]
+use crate::day2::Day2;
+#[cfg(feature = "day3")]
+use crate::day3::Day3;
+#[cfg(feature = "day4")]
+use crate::day4::Day4;
+#[cfg(feature = "day5")]
+use crate::day5::Day5;
+#[cfg(feature = "day6")]
+use crate::day6::Day6;
+#[cfg(feature = "day7")]
+use crate::day7::Day7;
+#[cfg(feature = "day8")]
+use crate::day8::Day8;
+#[cfg(feature = "day9")]
+use crate::day9::Day9;
+use crate::error::ParseError;
+use crate::solution::{Solution, Streaming, Trace, Visualize, Windowed};
+use crate::stream::StreamError;
+use crate::trace::Terminal;
+use crate::viz::Output;
+use std::any::Any;
+use std::cell::Cell;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+/// The default directory containing the puzzle inputs and the recorded answers, as used by
+/// `cargo aoc`.
+pub const INPUT_DIR: &str = "input";
+
+/// The year of the puzzles, which is the name of the subdirectory that holds the inputs.
+const YEAR: &str = "2021";
+
+/// The parsed input of a puzzle, as returned by its generator.
+pub type Input = Box<dyn Any + Send + Sync>;
+
+/// The summary of a puzzle input that was read line by line, as returned by `Puzzle::summarize()`.
+pub struct Summary(Box<dyn Any + Send + Sync>);
+
+/// One of the two parts of a puzzle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Self; 2] = [Self::One, Self::Two];
+
+    /// Returns the part with the given number, if it exists.
+    pub const fn from_number(number: u8) -> Option<Self> {
+        match number {
+            1 => Some(Self::One),
+            2 => Some(Self::Two),
+            _ => None,
+        }
+    }
+
+    pub const fn number(self) -> u8 {
+        match self {
+            Self::One => 1,
+            Self::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+/// An error that prevented a puzzle from being solved.
+#[derive(Debug)]
+pub enum RunError {
+    /// The puzzle input could not be read.
+    Io { path: PathBuf, source: io::Error },
+    /// An output file could not be written.
+    Write { path: PathBuf, source: io::Error },
+    /// The answers file contains an invalid line.
+    Answers { path: PathBuf, line: usize },
+    /// The generator rejected the puzzle input.
+    Parse(ParseError),
+    /// The solver panicked.
+    Solve {
+        day: u8,
+        part: Part,
+        message: String,
+    },
+    /// The solver returned an answer that differs from the recorded answer.
+    WrongAnswer {
+        day: u8,
+        part: Part,
+        expected: String,
+        actual: Answer,
+    },
+}
+
+impl RunError {
+    /// Returns the exit code that the runner uses to report this error.
+    pub const fn exit_code(&self) -> u8 {
+        match self {
+            Self::Io { .. } | Self::Write { .. } | Self::Answers { .. } => 3,
+            Self::Parse(_) => 4,
+            Self::Solve { .. } => 5,
+            Self::WrongAnswer { .. } => 6,
+        }
+    }
+
+    /// Converts an error that occurred while reading the input at the given path.
+    pub fn from_stream(path: &Path, e: StreamError) -> Self {
+        match e {
+            StreamError::Io(source) => Self::Io {
+                path: path.to_path_buf(),
+                source,
+            },
+            StreamError::Parse(e) => Self::Parse(e),
+        }
+    }
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "cannot read {}: {}", path.display(), source),
+            Self::Write { path, source } => {
+                write!(f, "cannot write to {}: {}", path.display(), source)
+            }
+            Self::Answers { path, line } => {
+                write!(f, "invalid answer on line {} of {}", line, path.display())
+            }
+            Self::Parse(e) => write!(f, "cannot parse input: {}", e),
+            Self::Solve { day, part, message } => {
+                write!(f, "day {}, part {} failed: {}", day, part, message)
+            }
+            Self::WrongAnswer {
+                day,
+                part,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "day {}, part {}: expected {} but got {}",
+                day, part, expected, actual
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+impl From<ParseError> for RunError {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+/// A puzzle that can be solved by the runner.
+///
+/// This wraps the `Solution` of a day so it can be called without knowing the type of the parsed
+/// input and the answers.
+pub struct Puzzle {
+    pub day: u8,
+    parse: fn(&str) -> Result<Input, ParseError>,
+    parts: [fn(&Input) -> Answer; 2],
+    stream: Option<Stream>,
+    visualize: Option<fn(&Input, &mut Output) -> io::Result<()>>,
+    trace: Option<fn(&Input, &mut Terminal) -> io::Result<()>>,
+    window: Option<fn(&Input, usize) -> Answer>,
+    version: Option<u64>,
+}
+
+/// The functions of a solution that can read its input line by line.
+struct Stream {
+    summarize: fn(&mut dyn BufRead) -> Result<Summary, StreamError>,
+    parts: [fn(&Summary) -> Answer; 2],
+}
+
+impl Puzzle {
+    /// Wraps the solution of a day.
+    pub const fn new<S: Solution>() -> Self {
+        Self {
+            day: S::DAY,
+            parse: |input| Ok(Box::new(S::parse(input)?)),
+            parts: [
+                |input| S::part1(downcast::<S>(input)).into(),
+                |input| S::part2(downcast::<S>(input)).into(),
+            ],
+            stream: None,
+            visualize: None,
+            trace: None,
+            window: None,
+            version: None,
+        }
+    }
+
+    /// Adds support for reading the input line by line, for a solution that implements
+    /// `Streaming`.
+    pub const fn with_stream<S: Streaming>(self) -> Self {
+        Self {
+            stream: Some(Stream {
+                summarize: |reader| Ok(Summary(Box::new(S::summarize(reader)?))),
+                parts: [
+                    |summary| S::summary_part1(downcast_summary::<S>(summary)).into(),
+                    |summary| S::summary_part2(downcast_summary::<S>(summary)).into(),
+                ],
+            }),
+            ..self
+        }
+    }
+
+    /// Adds support for rendering the puzzle, for a solution that implements `Visualize`.
+    pub const fn with_visualization<S: Visualize>(self) -> Self {
+        Self {
+            visualize: Some(|input, output| S::visualize(downcast::<S>(input), output)),
+            ..self
+        }
+    }
+
+    /// Parses the puzzle input using the generator of the day.
+    pub fn parse(&self, input: &str) -> Result<Input, ParseError> {
+        (self.parse)(input)
+    }
+
+    /// Solves a part of the puzzle for an input that was returned by `parse()`.
+    ///
+    /// A panic in the solver is caught and returned as an error, so a single failing day does not
+    /// take down the runner.
+    pub fn solve(&self, part: Part, input: &Input) -> Result<Answer, RunError> {
+        let solver = self.parts[part.number() as usize - 1];
+        self.catch_panic(part, || solver(input))
+    }
+
+    /// Adds support for printing the intermediate state, for a solution that implements `Trace`.
+    pub const fn with_trace<S: Trace>(self) -> Self {
+        Self {
+            trace: Some(|input, terminal| S::trace(downcast::<S>(input), terminal)),
+            ..self
+        }
+    }
+
+    /// Adds support for solving part 2 with other window sizes, for a solution that implements
+    /// `Windowed`.
+    pub const fn with_window<S: Windowed>(self) -> Self {
+        Self {
+            window: Some(|input, size| S::part2_window(downcast::<S>(input), size).into()),
+            ..self
+        }
+    }
+
+    /// Sets the version of the solver to a fingerprint of its source, so answers in the `Cache` are
+    /// only used until the source changes.
+    pub const fn with_source(self, source: &str) -> Self {
+        Self {
+            version: Some(cache::fingerprint(source.as_bytes()) ^ SHARED_SOURCE),
+            ..self
+        }
+    }
+
+    /// Returns the version of the solver, or `None` if its answers cannot be cached.
+    pub const fn version(&self) -> Option<u64> {
+        self.version
+    }
+
+    /// Returns whether the puzzle input can be read line by line with `summarize()`.
+    pub const fn can_stream(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// Reads the puzzle input at the given path line by line, or from standard input if the path
+    /// is `-`, and summarises it. This only keeps the summary in memory, so the input can be larger
+    /// than the available memory. The summary is needed by both parts, so a panic while reading it
+    /// is reported as a failure of part 1.
+    ///
+    /// Returns `None` if the day does not support streaming.
+    pub fn summarize(&self, path: &Path) -> Option<Result<Summary, RunError>> {
+        let stream = self.stream.as_ref()?;
+        let summary = open_input(path)
+            .map_err(StreamError::from)
+            .map_err(|e| RunError::from_stream(path, e))
+            .and_then(|mut reader| self.catch_panic(Part::One, || (stream.summarize)(&mut reader)))
+            .and_then(|summary| summary.map_err(|e| RunError::from_stream(path, e)));
+        Some(summary)
+    }
+
+    /// Reads a puzzle input line by line from the given reader, and summarises it.
+    ///
+    /// Returns `None` if the day does not support streaming.
+    pub fn summarize_reader(
+        &self,
+        reader: &mut dyn BufRead,
+    ) -> Option<Result<Summary, StreamError>> {
+        let stream = self.stream.as_ref()?;
+        Some((stream.summarize)(reader))
+    }
+
+    /// Solves a part of the puzzle for a summary that was returned by `summarize()`.
+    pub fn solve_summary(&self, part: Part, summary: &Summary) -> Result<Answer, RunError> {
+        let stream = self
+            .stream
+            .as_ref()
+            .expect("the summary was returned by the same puzzle");
+        let solver = stream.parts[part.number() as usize - 1];
+        self.catch_panic(part, || solver(summary))
+    }
+
+    /// Returns whether the puzzle can be rendered with `visualize()`.
+    pub const fn can_visualize(&self) -> bool {
+        self.visualize.is_some()
+    }
+
+    /// Renders the puzzle for an input that was returned by `parse()`, writing the images to the
+    /// given directory. Returns the paths of the written files.
+    ///
+    /// Returns `None` if the day has no visualization.
+    pub fn visualize(&self, input: &Input, dir: &Path) -> Option<Result<Vec<PathBuf>, RunError>> {
+        let visualize = self.visualize?;
+        let result = Output::create(dir).and_then(|mut output| {
+            visualize(input, &mut output)?;
+            Ok(output.files().to_vec())
+        });
+        Some(result.map_err(|source| RunError::Write {
+            path: dir.to_path_buf(),
+            source,
+        }))
+    }
+
+    /// Returns whether the intermediate state can be printed with `trace()`.
+    pub const fn can_trace(&self) -> bool {
+        self.trace.is_some()
+    }
+
+    /// Solves the puzzle for an input that was returned by `parse()`, printing the intermediate
+    /// state to the terminal. The traces follow the solver of part 2, so a panic is reported as a
+    /// failure of part 2.
+    ///
+    /// Returns `None` if the day does not support tracing.
+    pub fn trace(&self, input: &Input, terminal: &mut Terminal) -> Option<Result<(), RunError>> {
+        let trace = self.trace?;
+        let result = self.catch_panic(Part::Two, || trace(input, terminal));
+        Some(result.and_then(|result| {
+            result.map_err(|source| RunError::Write {
+                path: PathBuf::from("-"),
+                source,
+            })
+        }))
+    }
+
+    /// Returns whether part 2 can be solved for other window sizes with `solve_window()`.
+    pub const fn can_window(&self) -> bool {
+        self.window.is_some()
+    }
+
+    /// Solves part 2 of the puzzle with windows of `size` values, for an input that was returned by
+    /// `parse()`.
+    ///
+    /// Returns `None` if the day does not compare windows.
+    pub fn solve_window(&self, input: &Input, size: usize) -> Option<Result<Answer, RunError>> {
+        let solver = self.window?;
+        Some(self.catch_panic(Part::Two, || solver(input, size)))
+    }
+
+    /// Calls a solver, returning a panic as an error. The hook of `silence_solver_panics()` does not
+    /// print the panic message meanwhile, since it is part of the error.
+    fn catch_panic<T>(&self, part: Part, solver: impl FnOnce() -> T) -> Result<T, RunError> {
+        let solving = SOLVING.replace(true);
+        let result = panic::catch_unwind(AssertUnwindSafe(solver));
+        SOLVING.set(solving);
+        result.map_err(|payload| RunError::Solve {
+            day: self.day,
+            part,
+            message: panic_message(payload.as_ref()),
+        })
+    }
+}
+
+/// The location of a set of puzzle inputs and their recorded answers.
+///
+/// Inputs are stored in `<dir>/2021/dayN.txt`, as used by `cargo aoc`. Every account gets different
+/// inputs, so the inputs of other accounts can be kept apart in profiles, which are stored in
+/// `<dir>/<profile>/2021/dayN.txt`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Inputs {
+    dir: PathBuf,
+    profile: Option<String>,
+}
+
+impl Default for Inputs {
+    fn default() -> Self {
+        Self::new(INPUT_DIR, None)
+    }
+}
+
+impl Inputs {
+    pub fn new(dir: impl Into<PathBuf>, profile: Option<String>) -> Self {
+        Self {
+            dir: dir.into(),
+            profile,
+        }
+    }
+
+    /// Returns the inputs of all profiles in the given directory, ordered by name. The inputs
+    /// without a profile come first, if they exist.
+    pub fn profiles(dir: &Path) -> Result<Vec<Self>, RunError> {
+        let io_error = |source| RunError::Io {
+            path: dir.to_path_buf(),
+            source,
+        };
+        let mut profiles = vec![];
+        for entry in std::fs::read_dir(dir).map_err(io_error)? {
+            let entry = entry.map_err(io_error)?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name != YEAR && entry.path().join(YEAR).is_dir() {
+                profiles.push(Self::new(dir, Some(name)));
+            }
+        }
+        profiles.sort_by(|a, b| a.profile.cmp(&b.profile));
+        if dir.join(YEAR).is_dir() {
+            profiles.insert(0, Self::new(dir, None));
+        }
+        Ok(profiles)
+    }
+
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Returns the location of the puzzle input of the given day.
+    pub fn input_path(&self, day: u8) -> PathBuf {
+        self.year_dir().join(format!("day{}.txt", day))
+    }
+
+    /// Returns the location of the recorded answers.
+    pub fn answers_path(&self) -> PathBuf {
+        self.year_dir().join("answers.txt")
+    }
+
+    fn year_dir(&self) -> PathBuf {
+        match &self.profile {
+            Some(profile) => self.dir.join(profile).join(YEAR),
+            None => self.dir.join(YEAR),
+        }
+    }
+}
+
+/// Downcasts a type erased input to the input type of the given solution.
+fn downcast<S: Solution>(input: &Input) -> &S::Input {
+    input
+        .downcast_ref()
+        .expect("the input was returned by the generator of the same day")
+}
+
+/// Downcasts a type erased summary to the summary type of the given solution.
+fn downcast_summary<S: Streaming>(summary: &Summary) -> &S::Summary {
+    summary
+        .0
+        .downcast_ref()
+        .expect("the summary was returned by the same day")
+}
+
+/// A fingerprint of the modules that every solver depends on, which is part of every version.
+const SHARED_SOURCE: u64 = cache::fingerprint(
+    concat!(
+        include_str!("answer.rs"),
+        include_str!("error.rs"),
+        include_str!("grid.rs")
+    )
+    .as_bytes(),
+);
+
+/// The puzzles of the days that are enabled by their cargo feature.
+static PUZZLES: &[Puzzle] = &[
+    #[cfg(feature = "day1")]
+    Puzzle::new::<Day1>()
+        .with_source(include_str!("day1.rs"))
+        .with_stream::<Day1>()
+        .with_window::<Day1>(),
+    #[cfg(feature = "day2")]
+    Puzzle::new::<Day2>()
+        .with_source(include_str!("day2.rs"))
+        .with_stream::<Day2>(),
+    #[cfg(feature = "day3")]
+    Puzzle::new::<Day3>()
+        .with_source(include_str!("day3.rs"))
+        .with_stream::<Day3>(),
+    #[cfg(feature = "day4")]
+    Puzzle::new::<Day4>()
+        .with_source(include_str!("day4.rs"))
+        .with_visualization::<Day4>()
+        .with_trace::<Day4>(),
+    #[cfg(feature = "day5")]
+    Puzzle::new::<Day5>()
+        .with_source(include_str!("day5.rs"))
+        .with_visualization::<Day5>(),
+    #[cfg(feature = "day6")]
+    Puzzle::new::<Day6>()
+        .with_source(include_str!("day6.rs"))
+        .with_stream::<Day6>(),
+    #[cfg(feature = "day7")]
+    Puzzle::new::<Day7>()
+        .with_source(include_str!("day7.rs"))
+        .with_stream::<Day7>(),
+    #[cfg(feature = "day8")]
+    Puzzle::new::<Day8>().with_source(include_str!("day8.rs")),
+    #[cfg(feature = "day9")]
+    Puzzle::new::<Day9>()
+        .with_source(include_str!("day9.rs"))
+        .with_visualization::<Day9>()
+        .with_trace::<Day9>(),
+    #[cfg(feature = "day10")]
+    Puzzle::new::<Day10>()
+        .with_source(include_str!("day10.rs"))
+        .with_stream::<Day10>()
+        .with_trace::<Day10>(),
+];
+
+/// Returns all puzzles that have been solved and are enabled by their feature, ordered by day.
+pub fn puzzles() -> &'static [Puzzle] {
+    PUZZLES
+}
+
+/// Returns the puzzle for the given day, if it has been solved.
+pub fn puzzle(day: u8) -> Option<&'static Puzzle> {
+    PUZZLES.iter().find(|p| p.day == day)
+}
+
+/// Reads a puzzle input from a file, or from standard input if the path is `-`.
+pub fn read_input(path: &Path) -> Result<String, RunError> {
+    let io_error = |source| RunError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    if path == Path::new("-") {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input).map_err(io_error)?;
+        Ok(input)
+    } else {
+        std::fs::read_to_string(path).map_err(io_error)
+    }
+}
+
+/// Opens the puzzle input at the given path for reading line by line, or standard input if the
+/// path is `-`.
+pub fn open_input(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    if path == Path::new("-") {
+        Ok(Box::new(io::stdin().lock()))
+    } else {
+        Ok(Box::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+thread_local! {
+    /// Whether the current thread is running a solver in `Puzzle::catch_panic()`.
+    static SOLVING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Installs a panic hook that does not print the panics of solvers, which are caught and reported
+/// by the runner. Other panics are still printed by the previous hook.
+pub fn silence_solver_panics() {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if !SOLVING.get() {
+            hook(info);
+        }
+    }));
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("the solver panicked")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn test_solve() {
+        let puzzle = puzzle(1).unwrap();
+        let input = puzzle.parse("199\n200\n208\n210\n200\n").unwrap();
+        assert_eq!(Answer::Integer(3), puzzle.solve(Part::One, &input).unwrap());
+        assert_eq!(Answer::Integer(1), puzzle.solve(Part::Two, &input).unwrap());
+        assert!(puzzle.can_window());
+        assert_eq!(
+            Answer::Integer(2),
+            puzzle.solve_window(&input, 2).unwrap().unwrap()
+        );
+        assert!(matches!(
+            puzzle.parse("199\nabc\n"),
+            Err(ParseError::InvalidNumber { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "day4", feature = "day7"))]
+    fn test_summarize() {
+        let puzzle = puzzle(7).unwrap();
+        let path = std::env::temp_dir().join("advent_of_code_2021_test_summarize.txt");
+        std::fs::write(&path, "16,1,2,0,4,2,7,1,2,14\n").unwrap();
+        let summary = puzzle.summarize(&path).unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            Answer::Integer(168),
+            puzzle.solve_summary(Part::Two, &summary).unwrap()
+        );
+
+        let error = puzzle.summarize(Path::new("missing.txt")).unwrap();
+        assert_eq!(3, error.err().unwrap().exit_code());
+        assert!(self::puzzle(4).unwrap().summarize(&path).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "day10")]
+    fn test_summarize_failure() {
+        // The completion score of 29 open chunks does not fit in a `u64`.
+        let puzzle = puzzle(10).unwrap();
+        let path = std::env::temp_dir().join("advent_of_code_2021_test_summarize_failure.txt");
+        std::fs::write(&path, "(".repeat(29)).unwrap();
+        let result = puzzle.summarize(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            result,
+            Err(RunError::Solve {
+                day: 10,
+                part: Part::One,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_inputs() {
+        let inputs = Inputs::default();
+        assert_eq!(Path::new("input/2021/day9.txt"), inputs.input_path(9));
+        let inputs = Inputs::new("data", Some(String::from("alice")));
+        assert_eq!(Path::new("data/alice/2021/day9.txt"), inputs.input_path(9));
+        assert_eq!(
+            Path::new("data/alice/2021/answers.txt"),
+            inputs.answers_path()
+        );
+
+        let dir = std::env::temp_dir().join("advent_of_code_2021_test_inputs");
+        for profile in ["2021", "bob/2021", "alice/2021", "notes"] {
+            std::fs::create_dir_all(dir.join(profile)).unwrap();
+        }
+        let profiles = Inputs::profiles(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            vec![None, Some("alice"), Some("bob")],
+            profiles.iter().map(Inputs::profile).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "day4")]
+    fn test_solve_failure() {
+        // Day 4 has no winning board if none of the drawn numbers form a line.
+        let puzzle = puzzle(4).unwrap();
+        let input = puzzle
+            .parse("1,2\n\n 1  2  3  4  5\n 6  7  8  9 10\n11 12 13 14 15\n16 17 18 19 20\n21 22 23 24 25\n")
+            .unwrap();
+        let error = puzzle.solve(Part::One, &input).unwrap_err();
+        assert_eq!(5, error.exit_code());
+    }
+
+    #[test]
+    #[cfg(feature = "day10")]
+    fn test_solve_without_answer() {
+        // Part 2 of day 10 has no answer if every line is corrupted.
+        let puzzle = puzzle(10).unwrap();
+        let input = puzzle.parse("(]\n").unwrap();
+        let summary = puzzle
+            .summarize_reader(&mut "(]\n".as_bytes())
+            .unwrap()
+            .unwrap();
+        for error in [
+            puzzle.solve(Part::Two, &input).unwrap_err(),
+            puzzle.solve_summary(Part::Two, &summary).unwrap_err(),
+        ] {
+            assert_eq!(
+                "day 10, part 2 failed: at least one line is not corrupted",
+                error.to_string()
+            );
+        }
+        assert_eq!(
+            Answer::Integer(57),
+            puzzle.solve(Part::One, &input).unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "day10")]
+    fn test_trace_failure() {
+        // Part 2 of day 10 has no answer if every line is corrupted.
+        let puzzle = puzzle(10).unwrap();
+        let input = puzzle.parse("(]\n").unwrap();
+        let mut out = vec![];
+        let mut terminal = Terminal::new(&mut out, None);
+        assert!(matches!(
+            puzzle.trace(&input, &mut terminal),
+            Some(Err(RunError::Solve {
+                day: 10,
+                part: Part::Two,
+                ..
+            }))
+        ));
+    }
+}
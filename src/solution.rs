@@ -0,0 +1,83 @@
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::stream::StreamError;
+use crate::trace::Terminal;
+use crate::viz::Output;
+use std::io::{self, BufRead};
+
+/// The solution of the puzzle of a day.
+///
+/// Every day implements this trait on a unit struct, e.g. `day9::Day9`. The solutions of all days
+/// can be enumerated through `puzzles()`, which wraps them so they can be called without knowing
+/// their types.
+///
+/// ```
+/// # #[cfg(feature = "day6")] {
+/// use advent_of_code_2021::day6::Day6;
+/// use advent_of_code_2021::Solution;
+///
+/// let school = Day6::parse("3,4,3,1,2").unwrap();
+/// assert_eq!(5934, Day6::part1(&school));
+/// # }
+/// ```
+pub trait Solution {
+    /// The day of the puzzle.
+    const DAY: u8;
+
+    /// The puzzle input, as returned by the generator.
+    type Input: Send + Sync + 'static;
+    /// The answer of part 1. The runner converts it to an `Answer`.
+    type Answer1: Into<Answer>;
+    /// The answer of part 2. The runner converts it to an `Answer`.
+    type Answer2: Into<Answer>;
+
+    /// Parses the puzzle input.
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Answer1;
+    fn part2(input: &Self::Input) -> Self::Answer2;
+}
+
+/// A solution that can also read its input line by line, for inputs too large to load in memory.
+///
+/// While reading, the input is reduced to a summary that only holds what the solution needs, e.g.
+/// counts instead of lines. Both parts are then solved from the summary. Most summaries have a size
+/// that does not depend on the number of lines, but some still grow with the input, e.g. day 10
+/// keeps a score for every incomplete line.
+///
+/// ```
+/// # #[cfg(feature = "day7")] {
+/// use advent_of_code_2021::day7::Day7;
+/// use advent_of_code_2021::Streaming;
+///
+/// let positions = Day7::summarize("16,1,2,0,4,2,7,1,2,14\n".as_bytes()).unwrap();
+/// assert_eq!(37, Day7::summary_part1(&positions));
+/// # }
+/// ```
+pub trait Streaming: Solution {
+    /// The summary of the input.
+    type Summary: Send + Sync + 'static;
+
+    /// Reads and summarises the puzzle input.
+    fn summarize(reader: impl BufRead) -> Result<Self::Summary, StreamError>;
+    fn summary_part1(summary: &Self::Summary) -> Self::Answer1;
+    fn summary_part2(summary: &Self::Summary) -> Self::Answer2;
+}
+
+/// A solution whose second part compares sliding windows of the input, which can also be solved
+/// for other window sizes.
+pub trait Windowed: Solution {
+    /// Solves part 2 with windows of `size` values instead of the size of the puzzle.
+    fn part2_window(input: &Self::Input, size: usize) -> Self::Answer2;
+}
+
+/// A solution that can render its puzzle as images, using the `viz` module.
+pub trait Visualize: Solution {
+    /// Renders the puzzle for the given input, writing the images to the output directory.
+    fn visualize(input: &Self::Input, output: &mut Output) -> io::Result<()>;
+}
+
+/// A solution that can print its intermediate state to a terminal, using the `trace` module.
+pub trait Trace: Solution {
+    /// Solves the puzzle for the given input, printing a frame for every step.
+    fn trace(input: &Self::Input, terminal: &mut Terminal) -> io::Result<()>;
+}
@@ -0,0 +1,149 @@
+#[cfg(any(feature = "day6", feature = "day7"))]
+use crate::error::end_of_input;
+use crate::error::ParseError;
+use std::error::Error;
+use std::fmt;
+use std::io;
+#[cfg(any(feature = "day6", feature = "day7"))]
+use std::io::BufRead;
+#[cfg(any(feature = "day6", feature = "day7"))]
+use std::str::FromStr;
+
+/// An error that occurred while reading a puzzle input line by line.
+#[derive(Debug)]
+pub enum StreamError {
+    /// The input could not be read.
+    Io(io::Error),
+    /// The input could not be parsed.
+    Parse(ParseError),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for StreamError {}
+
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ParseError> for StreamError {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+/// Reads the numbers on the first line of an input, separated by `separator`, with their 1-based
+/// column.
+///
+/// This is the streaming counterpart of `parse_list()`. Only a single number is kept in memory at a
+/// time, so the line can be arbitrarily long.
+#[cfg(any(feature = "day6", feature = "day7"))]
+pub(crate) fn read_list<T: FromStr>(
+    day: u8,
+    reader: impl BufRead,
+    separator: u8,
+) -> impl Iterator<Item = Result<(usize, T), StreamError>> {
+    ListReader {
+        day,
+        reader,
+        separator,
+        column: 1,
+        done: false,
+    }
+    .map(move |item| {
+        let (column, text) = item?;
+        let value = text.parse().map_err(|_| ParseError::InvalidNumber {
+            day,
+            line: 1,
+            column,
+            text,
+        })?;
+        Ok((column, value))
+    })
+}
+
+/// Returns the values on the first line of an input one by one, with their column.
+#[cfg(any(feature = "day6", feature = "day7"))]
+struct ListReader<R> {
+    day: u8,
+    reader: R,
+    separator: u8,
+    column: usize,
+    done: bool,
+}
+
+#[cfg(any(feature = "day6", feature = "day7"))]
+impl<R: BufRead> Iterator for ListReader<R> {
+    type Item = Result<(usize, String), StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut value = vec![];
+        let read = match self.reader.read_until(self.separator, &mut value) {
+            Ok(read) => read,
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e.into()));
+            }
+        };
+        if read == 0 && self.column == 1 {
+            self.done = true;
+            return Some(Err(end_of_input(self.day, "").into()));
+        }
+        if value.last() == Some(&self.separator) {
+            value.pop();
+        } else {
+            self.done = true;
+        }
+        // The list ends at the end of the first line.
+        if let Some(end) = value.iter().position(|b| *b == b'\n') {
+            value.truncate(end);
+            self.done = true;
+        }
+        if value.last() == Some(&b'\r') {
+            value.pop();
+        }
+
+        let text = String::from_utf8_lossy(&value).into_owned();
+        let column = self.column;
+        self.column += text.chars().count() + 1;
+        Some(Ok((column, text)))
+    }
+}
+
+#[cfg(all(test, any(feature = "day6", feature = "day7")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_list() {
+        let values: Result<Vec<(usize, u8)>, _> =
+            read_list(6, "3,14,3\n1,2\n".as_bytes(), b',').collect();
+        assert_eq!(vec![(1, 3), (3, 14), (6, 3)], values.unwrap());
+
+        let error = read_list::<u8>(6, "3,4,,3".as_bytes(), b',')
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            StreamError::Parse(ParseError::InvalidNumber { column: 5, .. })
+        ));
+
+        let error = read_list::<u8>(6, "".as_bytes(), b',').next().unwrap();
+        assert!(matches!(
+            error,
+            Err(StreamError::Parse(ParseError::UnexpectedEnd { .. }))
+        ));
+    }
+}
@@ -0,0 +1,107 @@
+//! Rendering of the intermediate state of the solvers in a terminal.
+//!
+//! Solvers that support tracing call a hook at every step of their main loop. The hooks render the
+//! state as a frame of text, coloured with ANSI escape codes.
+
+use crate::viz::Color;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+/// Writes the frames of a trace to a terminal.
+pub struct Terminal<'a> {
+    out: &'a mut dyn Write,
+    delay: Option<Duration>,
+    frames: usize,
+}
+
+impl<'a> Terminal<'a> {
+    /// Without a delay, frames are printed one after the other so they can be scrolled through,
+    /// e.g. with `less -R`. With a delay, the screen is cleared before every frame so the frames
+    /// play as an animation.
+    pub fn new(out: &'a mut dyn Write, delay: Option<Duration>) -> Self {
+        Self {
+            out,
+            delay,
+            frames: 0,
+        }
+    }
+
+    /// Prints a frame, preceded by a title line.
+    pub fn frame(&mut self, title: &str, body: &str) -> io::Result<()> {
+        self.frames += 1;
+        if self.delay.is_some() {
+            write!(self.out, "\x1b[2J\x1b[H")?;
+        }
+        writeln!(self.out, "{}", bold(format!("[{}] {}", self.frames, title)))?;
+        writeln!(self.out, "{}", body.trim_end())?;
+        if self.delay.is_none() {
+            writeln!(self.out)?;
+        }
+        self.out.flush()?;
+        if let Some(delay) = self.delay {
+            thread::sleep(delay);
+        }
+        Ok(())
+    }
+
+    /// Returns the number of frames that have been printed.
+    pub const fn frames(&self) -> usize {
+        self.frames
+    }
+}
+
+/// Returns the text in the given foreground colour.
+pub fn fg(text: impl Display, color: Color) -> String {
+    format!(
+        "\x1b[38;2;{};{};{}m{}\x1b[0m",
+        color[0], color[1], color[2], text
+    )
+}
+
+/// Returns the text on a background of the given colour.
+pub fn bg(text: impl Display, color: Color) -> String {
+    format!(
+        "\x1b[48;2;{};{};{}m{}\x1b[0m",
+        color[0], color[1], color[2], text
+    )
+}
+
+pub fn bold(text: impl Display) -> String {
+    format!("\x1b[1m{}\x1b[0m", text)
+}
+
+pub fn dim(text: impl Display) -> String {
+    format!("\x1b[2m{}\x1b[0m", text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::viz;
+
+    #[test]
+    fn test_frame() {
+        let mut out = vec![];
+        let mut terminal = Terminal::new(&mut out, None);
+        terminal.frame("Step", &fg("x", viz::RED)).unwrap();
+        terminal.frame("Step", "y\n").unwrap();
+        assert_eq!(2, terminal.frames());
+        assert_eq!(
+            "\x1b[1m[1] Step\x1b[0m\n\x1b[38;2;220;50;47mx\x1b[0m\n\n\x1b[1m[2] Step\x1b[0m\ny\n\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_animation() {
+        let mut out = vec![];
+        let mut terminal = Terminal::new(&mut out, Some(Duration::ZERO));
+        terminal.frame("Step", "x").unwrap();
+        assert_eq!(
+            "\x1b[2J\x1b[H\x1b[1m[1] Step\x1b[0m\nx\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! Rendering of puzzles as images.
+//!
+//! Images are written in formats that need no libraries to encode: PPM for pixel images and SVG for
+//! drawings. Animations are written as numbered frames, which can be turned into a video with e.g.
+//! `ffmpeg -i bingo-%04d.ppm bingo.mp4`.
+
+use crate::grid::Grid;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A colour as red, green and blue components.
+pub type Color = [u8; 3];
+
+pub const BLACK: Color = [0, 0, 0];
+pub const WHITE: Color = [255, 255, 255];
+pub const RED: Color = [220, 50, 47];
+pub const GREEN: Color = [133, 153, 0];
+pub const YELLOW: Color = [181, 137, 0];
+pub const GREY: Color = [88, 110, 117];
+
+/// Returns a colour on a gradient from dark blue at 0.0, through purple, to yellow at 1.0.
+pub fn gradient(t: f64) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    let channel = |from: f64, to: f64| (from + (to - from) * t).round() as u8;
+    [
+        channel(20.0, 250.0),
+        channel(10.0, 230.0),
+        channel(80.0, 30.0),
+    ]
+}
+
+/// Returns the colour with the given index in a palette of colours that are easy to tell apart.
+///
+/// Consecutive indices are spread around the colour wheel by the golden angle, so neighbouring
+/// indices never get similar colours.
+pub fn palette(index: usize) -> Color {
+    let hue = (index as f64 * 137.508) % 360.0;
+    hsv(hue, 0.65, 0.9)
+}
+
+/// Scales the brightness of a colour, where 0.0 is black and 1.0 is the colour itself.
+pub fn shade(color: Color, brightness: f64) -> Color {
+    color.map(|c| (c as f64 * brightness.clamp(0.0, 1.0)).round() as u8)
+}
+
+fn hsv(hue: f64, saturation: f64, value: f64) -> Color {
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = value - chroma;
+    [r, g, b].map(|c| ((c + m) * 255.0).round() as u8)
+}
+
+/// Encodes an image as a binary PPM file. Every cell of the grid becomes a square of `scale` by
+/// `scale` pixels.
+pub fn ppm(image: &Grid<Color>, scale: usize) -> Vec<u8> {
+    let scale = scale.max(1);
+    let (width, height) = (image.width() * scale, image.height() * scale);
+    let mut data = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    data.reserve(width * height * 3);
+    for row in image.rows() {
+        for _ in 0..scale {
+            for color in row {
+                for _ in 0..scale {
+                    data.extend_from_slice(color);
+                }
+            }
+        }
+    }
+    data
+}
+
+/// A drawing that is encoded as an SVG file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Svg {
+    width: usize,
+    height: usize,
+    elements: String,
+}
+
+impl Svg {
+    /// Starts a drawing with a black background.
+    pub fn new(width: usize, height: usize) -> Self {
+        let mut svg = Self {
+            width,
+            height,
+            elements: String::new(),
+        };
+        svg.rect(0, 0, width, height, BLACK);
+        svg
+    }
+
+    pub fn rect(&mut self, x: usize, y: usize, width: usize, height: usize, fill: Color) {
+        writeln!(
+            self.elements,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+            x,
+            y,
+            width,
+            height,
+            hex(fill)
+        )
+        .unwrap();
+    }
+
+    /// Draws a line between the centres of two cells, so lines through the same cells overlap.
+    pub fn line(&mut self, from: (usize, usize), to: (usize, usize), stroke: Color) {
+        writeln!(
+            self.elements,
+            r#"<line x1="{}.5" y1="{}.5" x2="{}.5" y2="{}.5" stroke="{}" stroke-linecap="square" stroke-opacity="0.6"/>"#,
+            from.0,
+            from.1,
+            to.0,
+            to.1,
+            hex(stroke)
+        )
+        .unwrap();
+    }
+}
+
+impl fmt::Display for Svg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            self.width, self.height
+        )?;
+        write!(f, "{}", self.elements)?;
+        writeln!(f, "</svg>")
+    }
+}
+
+fn hex(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+/// A directory that the images of a visualisation are written to.
+#[derive(Debug)]
+pub struct Output {
+    dir: PathBuf,
+    files: Vec<PathBuf>,
+}
+
+impl Output {
+    /// Creates the directory if it does not exist yet.
+    pub fn create(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            files: vec![],
+        })
+    }
+
+    /// Writes a file to the directory, replacing an existing file with the same name.
+    pub fn write(&mut self, name: &str, contents: impl AsRef<[u8]>) -> io::Result<()> {
+        let path = self.dir.join(name);
+        fs::write(&path, contents)?;
+        self.files.push(path);
+        Ok(())
+    }
+
+    /// Writes a frame of an animation. The frame number is added to the name before the extension,
+    /// e.g. frame 3 of `bingo.ppm` is written to `bingo-0003.ppm`.
+    pub fn write_frame(
+        &mut self,
+        name: &str,
+        frame: usize,
+        contents: impl AsRef<[u8]>,
+    ) -> io::Result<()> {
+        let name = match name.rsplit_once('.') {
+            Some((stem, extension)) => format!("{}-{:04}.{}", stem, frame, extension),
+            None => format!("{}-{:04}", name, frame),
+        };
+        self.write(&name, contents)
+    }
+
+    /// Returns the paths of the files that have been written, in order.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ppm() {
+        let image = Grid::from([[BLACK, WHITE]]);
+        let data = ppm(&image, 2);
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(header, &data[..header.len()]);
+        assert_eq!(header.len() + 4 * 2 * 3, data.len());
+        assert_eq!(
+            [0, 0, 0, 0, 0, 0, 255, 255, 255, 255, 255, 255],
+            data[header.len()..header.len() + 12]
+        );
+    }
+
+    #[test]
+    fn test_svg() {
+        let mut svg = Svg::new(10, 10);
+        svg.line((0, 9), (5, 9), RED);
+        let svg = svg.to_string();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains(r##"<rect x="0" y="0" width="10" height="10" fill="#000000"/>"##));
+        assert!(svg.contains(r##"x1="0.5" y1="9.5" x2="5.5" y2="9.5" stroke="#dc322f""##));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn test_output() {
+        let dir = std::env::temp_dir().join("advent_of_code_2021_test_output");
+        let mut output = Output::create(&dir).unwrap();
+        output.write("image.svg", "<svg/>").unwrap();
+        output.write_frame("frame.ppm", 12, [1, 2, 3]).unwrap();
+        assert_eq!(
+            vec![dir.join("image.svg"), dir.join("frame-0012.ppm")],
+            output.files()
+        );
+        assert_eq!(vec![1, 2, 3], fs::read(dir.join("frame-0012.ppm")).unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,60 @@
+//! JavaScript bindings for the solutions, so they can run in a browser.
+//!
+//! This module is only built with the `wasm` feature. The bindings are generated with
+//! `wasm-bindgen` from a build for the `wasm32-unknown-unknown` target, see the README.
+
+use crate::runner::{self, Part, RunError};
+use wasm_bindgen::prelude::*;
+
+/// Solves a part of the puzzle of a day for the given input, and returns the answer as text.
+///
+/// An unknown day or part and an invalid input throw an `Error` in JavaScript. A solver that panics
+/// aborts the WebAssembly instance instead, since panics cannot be caught on
+/// `wasm32-unknown-unknown`.
+#[wasm_bindgen]
+pub fn solve(day: u8, part: u8, input: &str) -> Result<String, JsError> {
+    answer(day, part, input).map_err(|e| JsError::new(&e))
+}
+
+/// Returns the days that have been solved, in order.
+#[wasm_bindgen]
+pub fn days() -> Vec<u8> {
+    runner::puzzles().iter().map(|p| p.day).collect()
+}
+
+/// Solves a part of a puzzle, with the errors as messages for the user.
+fn answer(day: u8, part: u8, input: &str) -> Result<String, String> {
+    let puzzle = runner::puzzle(day).ok_or_else(|| format!("day {} has not been solved", day))?;
+    let part = Part::from_number(part).ok_or_else(|| format!("part {} does not exist", part))?;
+    let input = puzzle
+        .parse(input)
+        .map_err(|e| RunError::from(e).to_string())?;
+    let answer = puzzle.solve(part, &input).map_err(|e| e.to_string())?;
+    Ok(answer.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "day6")]
+    fn test_answer() {
+        assert_eq!(Ok(String::from("5934")), answer(6, 1, "3,4,3,1,2\n"));
+        assert_eq!(Ok(String::from("26984457539")), answer(6, 2, "3,4,3,1,2\n"));
+        assert_eq!(Err(String::from("part 3 does not exist")), answer(6, 3, ""));
+        assert!(answer(6, 1, "3,x\n")
+            .unwrap_err()
+            .starts_with("cannot parse input"));
+    }
+
+    #[test]
+    fn test_days() {
+        assert_eq!(
+            Err(String::from("day 25 has not been solved")),
+            answer(25, 1, "")
+        );
+        let enabled: Vec<u8> = runner::puzzles().iter().map(|p| p.day).collect();
+        assert_eq!(enabled, days());
+    }
+}